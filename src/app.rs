@@ -1,11 +1,52 @@
+use crate::audit::{AuditLog, Event};
+use crate::fairness::Adjusted;
+use crate::game::physics::SUBSTEPS_PER_FRAME;
 use crate::game::GameState;
+use crate::history::{Draw, History};
+use crate::proof::Inputs;
+use crate::roster::{Entry, Roster, Rosters};
+use crate::tournament::Tournament;
+use crate::ui::audit_panel::AuditView;
+use crate::ui::history_panel::HistoryView;
+use crate::ui::palette;
+use crate::ui::roster_panel::RosterView;
+use crate::ui::skins::SkinTextures;
+use crate::ui::tournament_panel::{TournamentAction, TournamentView};
+use crate::ui::verify_panel::VerifyView;
 use eframe::egui;
 use rapier2d::prelude::{point, vector}; // Import point and vector macros
 
+// eframe storage keys
+const ROSTERS_KEY: &str = "rosters";
+const HISTORY_KEY: &str = "history";
+const AUDIT_KEY: &str = "audit_log";
+const TOURNAMENT_KEY: &str = "tournament";
+
 pub struct PinballApp {
     state: GameState,
     input_text: String,
+    roster: Roster,   // `input_text`, parsed
+    rosters: Rosters, // Saved between sessions
+    roster_view: RosterView,
+    skin_textures: SkinTextures,
+    history: History, // Saved between sessions
+    history_view: HistoryView,
+    draw_recorded: bool, // The current race is already in `history`
+    audit: AuditLog,     // Saved between sessions, never edited
+    audit_view: AuditView,
+    next_seed: u64,              // Committed to before the next race starts
+    race_inputs: Option<Inputs>, // The current (or last) race; its seed is shown once it's over
+    verify_view: VerifyView,
+    tournament: Option<Tournament>, // Saved between sessions until ended
+    tournament_view: TournamentView,
+    tournament_race: Option<(usize, usize)>, // (round, race) on the board
     // Configuration
+    show_photo_finish: bool,
+    photo_finish_index: usize,
+    debug_overlay: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    map_path: String,
+    status: Option<String>, // Result of the last file operation
 }
 
 impl PinballApp {
@@ -13,11 +54,10 @@ impl PinballApp {
         // Font Setup for Korean Support
         let mut fonts = egui::FontDefinitions::default();
 
-        // Load the font using include_bytes! (Embeds it in the WASM)
-        // Path is relative to this file (src/app.rs) -> ../assets/
+        // Font embedded in the binary (see `ui::FONT`)
         fonts.font_data.insert(
             "korean_font".to_owned(),
-            egui::FontData::from_static(include_bytes!("../assets/fonts/ChungBuk_70_Regular.ttf")),
+            egui::FontData::from_static(crate::ui::FONT),
         );
 
         // Put my font first (highest priority) for Proportional text:
@@ -36,38 +76,471 @@ impl PinballApp {
 
         cc.egui_ctx.set_fonts(fonts);
         cc.egui_ctx.set_visuals(egui::Visuals::dark()); // Neon Dark Mode
+        let rosters: Rosters = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Rosters>(storage, ROSTERS_KEY))
+            .filter(|rosters| !rosters.saved.is_empty()) // `active()` needs one
+            .unwrap_or_default();
+        let input_text = crate::roster::to_text(&rosters.active().entries);
         Self {
             state: GameState::new(),
-            input_text: "Alice*5\nBob*3".to_owned(),
+            roster: Roster::parse(&input_text),
+            input_text,
+            rosters,
+            roster_view: RosterView::default(),
+            skin_textures: SkinTextures::default(),
+            history: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, HISTORY_KEY))
+                .unwrap_or_default(),
+            history_view: HistoryView::default(),
+            draw_recorded: true,
+            audit: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, AUDIT_KEY))
+                .unwrap_or_default(),
+            audit_view: AuditView::default(),
+            next_seed: rand::random(),
+            race_inputs: None,
+            verify_view: VerifyView::default(),
+            tournament: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, TOURNAMENT_KEY))
+                .unwrap_or_default(),
+            tournament_view: TournamentView::default(),
+            tournament_race: None,
+            show_photo_finish: true,
+            photo_finish_index: 0,
+            debug_overlay: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            map_path: "map.json".to_owned(),
+            status: None,
         }
     }
 
+    /// Start the next race: the tournament's, while one is on, otherwise a
+    /// single draw from the roster.
     fn parse_and_spawn(&mut self) {
+        self.close_open_run();
         self.state.reset_game(); // Only clear balls
+        self.tournament_race = self.next_tournament_race().map(|(_, race)| race);
+        if self.next_map().id() != self.state.map.id() {
+            let map = self.next_map().clone();
+            self.state.load_map(map);
+        }
+        let seed = std::mem::replace(&mut self.next_seed, rand::random());
+        self.state.seed_race(seed);
+        self.draw_recorded = false;
+
+        let field = self.next_field();
+        let entrants = || field.iter().map(|(entry, tickets)| (entry, *tickets));
+        let inputs = Inputs::new(seed, entrants(), &self.state.map, &self.state);
+        let label = self.next_label();
+        self.audit.append(
+            Event::Started,
+            &label,
+            &inputs.commitment(),
+            None,
+            Vec::new(),
+        );
+        self.race_inputs = Some(inputs);
+        crate::roster::spawn_balls(&mut self.state, entrants());
+        self.state.is_running = true;
+        self.state.edit_mode = false; // Locked until the race is over
+        self.show_photo_finish = true;
+        self.photo_finish_index = 0;
+    }
+
+    /// Who races next, with their tickets: the next tournament race's field
+    /// while a tournament is on, otherwise the roster after fairness rules.
+    fn next_field(&self) -> Vec<(Entry, u32)> {
+        if let Some((t, (round, race))) = self.next_tournament_race() {
+            return t
+                .entries(round, race)
+                .into_iter()
+                .map(|e| {
+                    let tickets = e.tickets;
+                    (e, tickets)
+                })
+                .collect();
+        }
+        let tickets = self.effective_tickets().into_iter().map(|a| a.tickets);
+        self.roster.entrants().cloned().zip(tickets).collect()
+    }
+
+    /// The map the next race runs on.
+    fn next_map(&self) -> &crate::game::map_file::MapFile {
+        match self.next_tournament_race() {
+            Some((t, (round, _))) => &t.rounds[round].map,
+            None => &self.state.map,
+        }
+    }
+
+    /// What the next race is logged as in the audit log.
+    fn next_label(&self) -> String {
+        match self.next_tournament_race() {
+            Some((t, (round, race))) => format!("{} · {}", t.roster, t.race_label(round, race)),
+            None => self.rosters.active().name.clone(),
+        }
+    }
+
+    fn next_tournament_race(&self) -> Option<(&Tournament, (usize, usize))> {
+        let t = self.tournament.as_ref()?;
+        Some((t, t.next_race()?))
+    }
+
+    /// Tickets per entrant once the active roster's fairness rules apply.
+    fn effective_tickets(&self) -> Vec<Adjusted> {
+        let active = self.rosters.active();
+        active
+            .fairness
+            .apply(self.roster.entrants(), &self.history, &active.name)
+    }
 
-        let lines = self.input_text.lines();
-        for line in lines {
-            let parts: Vec<&str> = line.split('*').collect();
-            if parts.len() == 2 {
-                let name = parts[0].trim();
-                let count = parts[1].trim().parse::<usize>().unwrap_or(1);
-                for i in 1..=count {
-                    self.state.spawn_ball(format!("{}#{}", name, i));
+    /// Sidebar section for commit-reveal: the next draw's commitment up
+    /// front, the last draw's seed once it's over, and the verify window.
+    fn proof_section(&mut self, ui: &mut egui::Ui) {
+        let racing = self.state.is_running && !self.state.race_finished();
+        let next = (!racing).then(|| {
+            let field = self.next_field();
+            let entrants = field.iter().map(|(entry, tickets)| (entry, *tickets));
+            Inputs::new(self.next_seed, entrants, self.next_map(), &self.state).commitment()
+        });
+        let shown = match (&next, &self.race_inputs) {
+            (Some(next), _) => next.clone(),
+            (None, Some(inputs)) => inputs.commitment(),
+            (None, None) => String::new(),
+        };
+        egui::CollapsingHeader::new(format!("Provably Fair ({}…)", &shown[..shown.len().min(8)]))
+            .id_salt("provably_fair")
+            .show(ui, |ui| {
+                let hash_row = |ui: &mut egui::Ui, label: &str, hash: &str| {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.monospace(&hash[..16]).on_hover_text(hash);
+                        if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                            ui.ctx().copy_text(hash.to_owned());
+                        }
+                    });
+                };
+                if let Some(next) = &next {
+                    hash_row(ui, "Next draw:", next);
+                    ui.label(
+                        egui::RichText::new(
+                            "Publish this before pressing Start. It covers the seed, the \
+                             entrants and their tickets, the map and the winning rules.",
+                        )
+                        .small(),
+                    );
+                }
+                if let Some(inputs) = &self.race_inputs {
+                    ui.separator();
+                    if racing {
+                        hash_row(ui, "This draw:", &inputs.commitment());
+                        ui.label(
+                            egui::RichText::new("The seed is revealed at the finish.").small(),
+                        );
+                    } else if self.state.race_finished() {
+                        hash_row(ui, "Last draw:", &inputs.commitment());
+                        ui.label(format!("Seed: {}", inputs.seed));
+                        let winner = self.state.winner().map(|i| {
+                            self.state.owners[self.state.finished_balls[i].owner as usize].clone()
+                        });
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("📋 Copy Proof")
+                                .on_hover_text(
+                                    "Seed, rules, map id and roster; publish with the map file",
+                                )
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(inputs.reveal(winner.as_deref()));
+                                self.status =
+                                    Some("Copied the draw's proof to the clipboard".to_owned());
+                            }
+                            if ui.button("Verify This Draw").clicked() {
+                                self.verify_view.fill(inputs, winner.as_deref());
+                            }
+                        });
+                    }
+                }
+                if ui.button("Verify a Draw…").clicked() {
+                    self.verify_view.open = true;
+                }
+            });
+    }
+
+    fn export_buttons(&mut self, ui: &mut egui::Ui) {
+        use crate::export::{Format, Report};
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Export:");
+            for format in Format::FILES {
+                if ui.button(format.name()).clicked() {
+                    let report = Report::from_race(&self.state, &self.rosters.active().name);
+                    let result = crate::files::save_bytes(
+                        &report.file_name(format.extension()),
+                        report.render(format).as_bytes(),
+                        format.mime(),
+                    );
+                    self.status = Some(result.unwrap_or_else(|e| e));
                 }
-            } else if !line.is_empty() {
-                // Just one
-                self.state.spawn_ball(line.trim().to_string());
             }
+            if ui
+                .button("PNG")
+                .on_hover_text("Results certificate")
+                .clicked()
+            {
+                let report = Report::from_race(&self.state, &self.rosters.active().name);
+                let result = crate::certificate::png(&self.state, &report).and_then(|bytes| {
+                    crate::files::save_bytes(&report.file_name("png"), &bytes, "image/png")
+                });
+                self.status = Some(result.unwrap_or_else(|e| e));
+            }
+            if ui.button("📋 Copy").clicked() {
+                let report = Report::from_race(&self.state, &self.rosters.active().name);
+                ui.ctx().copy_text(report.render(Format::Text));
+                self.status = Some("Copied results to the clipboard".to_owned());
+            }
+        });
+        if let Some(status) = &self.status {
+            ui.label(egui::RichText::new(status).small());
+        }
+    }
+
+    fn save_map(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let file_name = self.map_path.clone();
+        #[cfg(target_arch = "wasm32")]
+        let file_name = format!("{}.json", self.state.map.name);
+
+        let result =
+            crate::files::save_text(&file_name, &self.state.map.to_json(), "application/json");
+        self.status = Some(result.unwrap_or_else(|e| e));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_map(&mut self) {
+        let result = crate::files::load_text(&self.map_path)
+            .and_then(|json| crate::game::map_file::MapFile::from_json(&json));
+        self.status = Some(match result {
+            Ok(map) => {
+                let message = format!("Loaded \"{}\"", map.name);
+                self.state.load_map(map);
+                message
+            }
+            Err(e) => e,
+        });
+    }
+
+    fn set_roster_text(&mut self, text: String) {
+        self.input_text = text;
+        self.roster_text_changed();
+    }
+
+    /// Re-parse the names box, keeping the saved roster in step when it's valid.
+    fn roster_text_changed(&mut self) {
+        self.roster = Roster::parse(&self.input_text);
+        if let Some(entries) = self.roster.entries() {
+            self.rosters.active_mut().entries = entries;
+        }
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in &dropped {
+            let name = crate::files::dropped_file_name(file);
+            let lower = name.to_lowercase();
+            if [".csv", ".tsv", ".txt"]
+                .iter()
+                .any(|ext| lower.ends_with(ext))
+            {
+                self.status = Some(match crate::files::dropped_file_text(file) {
+                    Some(text) => {
+                        self.set_roster_text(text);
+                        format!(
+                            "Imported {} participants from {}",
+                            self.roster.rows.len(),
+                            name
+                        )
+                    }
+                    None => format!("Could not read {}", name),
+                });
+                continue;
+            }
+            if [".png", ".jpg", ".jpeg"]
+                .iter()
+                .any(|ext| lower.ends_with(ext))
+            {
+                let result = crate::files::dropped_file_bytes(file)
+                    .ok_or_else(|| format!("Could not read {}", name))
+                    .and_then(|bytes| self.skin_textures.insert(ctx, &name, &bytes));
+                self.status = Some(match result {
+                    Ok(()) => format!("Loaded skin picture {}", name),
+                    Err(e) => e,
+                });
+                continue;
+            }
+            if !lower.ends_with(".json") {
+                continue;
+            }
+            let result = crate::files::dropped_file_text(file)
+                .ok_or_else(|| format!("Could not read {}", name))
+                .and_then(|json| crate::game::map_file::MapFile::from_json(&json));
+            self.status = Some(match result {
+                Ok(map) => {
+                    let message = format!("Loaded \"{}\"", map.name);
+                    self.state.load_map(map);
+                    message
+                }
+                Err(e) => e,
+            });
+        }
+    }
+
+    /// Log the run in progress (or the one cut off when the app last closed)
+    /// as aborted, revealing its seed if it's known.
+    fn close_open_run(&mut self) {
+        let Some(open) = self.audit.open_run() else {
+            return;
+        };
+        let (roster, commitment) = (open.roster.clone(), open.commitment.clone());
+        let seed = self
+            .race_inputs
+            .as_ref()
+            .filter(|inputs| inputs.commitment() == commitment)
+            .map(|inputs| inputs.seed);
+        self.audit
+            .append(Event::Aborted, &roster, &commitment, seed, Vec::new());
+    }
+
+    /// Aborted runs just before the current (or last) one, for a warning.
+    fn aborts_before_run(&self) -> usize {
+        self.audit
+            .records
+            .iter()
+            .rposition(|r| r.event == Event::Started)
+            .map_or(0, |i| self.audit.aborts_before(i))
+    }
+
+    fn tournament_action(&mut self, action: TournamentAction) {
+        match action {
+            TournamentAction::Start(settings) => {
+                let entrants = self
+                    .next_field()
+                    .into_iter()
+                    .map(|(entry, tickets)| Entry { tickets, ..entry })
+                    .collect();
+                let roster = &self.rosters.active().name;
+                match Tournament::new(roster, settings, entrants, self.state.map.clone()) {
+                    Ok(tournament) => {
+                        self.stop_and_reset();
+                        self.tournament = Some(tournament);
+                        self.status =
+                            Some("Tournament set up: press Start for the first race".to_owned());
+                    }
+                    Err(e) => self.status = Some(e),
+                }
+            }
+            TournamentAction::End => {
+                self.stop_and_reset();
+                self.tournament = None;
+            }
+        }
+    }
+
+    fn stop_and_reset(&mut self) {
+        self.close_open_run();
+        self.tournament_race = None;
+        self.state.is_running = false;
+        self.state.reset_game();
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context, time: f64) {
+        // Don't steal keys while typing names
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (pause, reset, step, faster, slower, fast_forward, debug) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::Space),
+                i.key_pressed(egui::Key::R),
+                i.key_pressed(egui::Key::Period),
+                i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals),
+                i.key_pressed(egui::Key::Minus),
+                i.key_pressed(egui::Key::F),
+                i.key_pressed(egui::Key::D),
+            )
+        });
+
+        if pause && self.state.is_running {
+            self.state.paused = !self.state.paused;
+        }
+        if reset {
+            self.stop_and_reset();
+        }
+        if step && self.state.paused {
+            self.state.single_step(SUBSTEPS_PER_FRAME, time);
+        }
+        if faster {
+            self.state.time_scale = (self.state.time_scale * 2.0).min(4.0);
+        }
+        if slower {
+            self.state.time_scale = (self.state.time_scale / 2.0).max(0.1);
+        }
+        if fast_forward {
+            self.state.start_fast_forward();
+        }
+        if debug {
+            self.debug_overlay = !self.debug_overlay;
         }
-        self.state.is_running = true;
     }
 }
 
 impl eframe::App for PinballApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, ROSTERS_KEY, &self.rosters);
+        eframe::set_value(storage, HISTORY_KEY, &self.history);
+        eframe::set_value(storage, AUDIT_KEY, &self.audit);
+        eframe::set_value(storage, TOURNAMENT_KEY, &self.tournament);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Game Loop
         let time = ctx.input(|i| i.time);
+        self.handle_shortcuts(ctx, time);
+        self.handle_dropped_files(ctx);
         self.state.update(time);
+        if self.state.race_finished() && !self.draw_recorded {
+            let entrants = self.race_inputs.as_ref().map(|i| i.entrants.clone());
+            let draw = Draw::from_race(
+                &self.state,
+                &self.rosters.active().name,
+                entrants.unwrap_or_default(),
+            );
+            let results: Vec<String> = draw.order.iter().map(|p| p.name.clone()).collect();
+            if let Some(inputs) = &self.race_inputs {
+                let label = self
+                    .audit
+                    .open_run()
+                    .map_or(draw.roster.clone(), |r| r.roster.clone());
+                self.audit.append(
+                    Event::Finished,
+                    &label,
+                    &inputs.commitment(),
+                    Some(inputs.seed),
+                    results.clone(),
+                );
+            }
+            // Tournament races count towards the tournament, not the draw history
+            match (self.tournament_race.take(), &mut self.tournament) {
+                (Some((round, race)), Some(tournament)) => {
+                    tournament.record(round, race, results, self.state.seed);
+                }
+                _ => self.history.draws.push(draw),
+            }
+            self.draw_recorded = true;
+        }
         if self.state.is_running {
             ctx.request_repaint(); // Animation
         }
@@ -75,19 +548,59 @@ impl eframe::App for PinballApp {
         // Sidebar
         egui::SidePanel::left("sidebar_panel").show(ctx, |ui| {
             ui.heading("Settings");
-            ui.label("Enter Names (Name*Count):");
-            ui.text_edit_multiline(&mut self.input_text);
+            if crate::ui::roster_panel::manager(ui, &mut self.rosters, &mut self.roster_view) {
+                let text = crate::roster::to_text(&self.rosters.active().entries);
+                self.set_roster_text(text);
+            }
+            ui.label("Enter Names (Name*Count, CSV or pasted spreadsheet columns):");
+            if ui.text_edit_multiline(&mut self.input_text).changed() {
+                self.roster_text_changed();
+            }
+            ui.label(
+                egui::RichText::new(
+                    "Columns: name, tickets, color, team, excluded, skin (emoji, \"initials\" \
+                     or a .png). Drop a .csv/.tsv to import, or a picture for skins.",
+                )
+                .small(),
+            );
+            crate::ui::roster_panel::show(ui, &self.roster);
+            let adjusted = self.effective_tickets();
+            crate::ui::fairness_panel::show(ui, &mut self.rosters.active_mut().fairness, &adjusted);
+            self.proof_section(ui);
 
-            if ui.button("Start Game").clicked() {
+            let (ready, label) = match self.next_tournament_race() {
+                Some((t, (round, race))) => (true, format!("Start {}", t.race_label(round, race))),
+                None => (
+                    self.roster.error_count() == 0 && adjusted.iter().any(|a| a.tickets > 0),
+                    "Start Game".to_owned(),
+                ),
+            };
+            if ui.add_enabled(ready, egui::Button::new(label)).clicked() {
                 self.parse_and_spawn();
             }
 
             if ui.button("Stop/Reset").clicked() {
-                self.state.is_running = false;
-                self.state.reset_game();
+                self.stop_and_reset();
+            }
+            let aborts = self.aborts_before_run();
+            if aborts > 0 && self.race_inputs.is_some() {
+                ui.colored_label(
+                    egui::Color32::ORANGE,
+                    format!("⚠ This run started after {} aborted run(s)", aborts),
+                )
+                .on_hover_text("See the audit log");
             }
 
-            if ui.button("Trigger Event (Drop Object)").clicked() {
+            // Obstacles aren't in the map, so they'd be outside the commitment
+            // mid-race and gone from the rebuilt world at the start
+            if ui
+                .add_enabled(
+                    self.state.is_running && self.state.race_finished(),
+                    egui::Button::new("Trigger Event (Drop Object)"),
+                )
+                .on_disabled_hover_text("Once the race is over")
+                .clicked()
+            {
                 self.state.spawn_event_obstacle();
             }
 
@@ -96,7 +609,96 @@ impl eframe::App for PinballApp {
             }
 
             ui.separator();
-            ui.checkbox(&mut self.state.edit_mode, "Edit Mode");
+            ui.horizontal(|ui| {
+                ui.label("Map:");
+                ui.text_edit_singleline(&mut self.state.map.name);
+            });
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.map_path);
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Save Map").clicked() {
+                    self.save_map();
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button("Load Map").clicked() {
+                    self.load_map();
+                }
+            });
+            ui.label(egui::RichText::new("Drop a map .json here to load it.").small());
+            if let Some(status) = &self.status {
+                ui.label(egui::RichText::new(status).small());
+            }
+            crate::ui::physics_panel::show(ui, &mut self.state);
+            crate::ui::logic_panel::show(ui, &mut self.state);
+
+            ui.separator();
+            ui.label("Playback:");
+            ui.horizontal(|ui| {
+                let label = if self.state.paused { "Resume" } else { "Pause" };
+                if ui
+                    .add_enabled(self.state.is_running, egui::Button::new(label))
+                    .clicked()
+                {
+                    self.state.paused = !self.state.paused;
+                }
+                let can_step = self.state.is_running && self.state.paused;
+                if ui
+                    .add_enabled(can_step, egui::Button::new("Step"))
+                    .on_hover_text("One physics step")
+                    .clicked()
+                {
+                    self.state.single_step(1, time);
+                }
+                if ui
+                    .add_enabled(can_step, egui::Button::new("Frame"))
+                    .on_hover_text("One frame of physics steps")
+                    .clicked()
+                {
+                    self.state.single_step(SUBSTEPS_PER_FRAME, time);
+                }
+            });
+            ui.add(
+                egui::Slider::new(&mut self.state.time_scale, 0.1..=4.0)
+                    .logarithmic(true)
+                    .text("Speed")
+                    .suffix("x"),
+            );
+            if self.state.fast_forwarding {
+                let total = self.state.balls.len() + self.state.finished_balls.len();
+                let done = self.state.finished_balls.len() as f32 / total.max(1) as f32;
+                ui.add(egui::ProgressBar::new(done).text(format!(
+                    "Fast-forwarding: {:.0} s raced, {} of {} finished",
+                    self.state.sim_time,
+                    self.state.finished_balls.len(),
+                    total
+                )));
+            } else if ui
+                .add_enabled(
+                    self.state.is_running,
+                    egui::Button::new("Fast-Forward to Finish"),
+                )
+                .clicked()
+            {
+                self.state.start_fast_forward();
+            }
+            ui.label(
+                egui::RichText::new("Space: pause  .: step  +/-: speed  F: fast-forward  R: reset")
+                    .small(),
+            );
+
+            ui.separator();
+            ui.checkbox(&mut self.debug_overlay, "Physics Debug Overlay (D)");
+            let locked = self.state.race_in_progress();
+            ui.add_enabled(
+                !locked,
+                egui::Checkbox::new(&mut self.state.edit_mode, "Edit Mode"),
+            );
+            if locked {
+                ui.label(egui::RichText::new("Locked until the race is over.").small());
+            }
             if self.state.edit_mode {
                 ui.label("Tools:");
                 ui.radio_value(
@@ -109,6 +711,189 @@ impl eframe::App for PinballApp {
                     crate::game::EditorTool::Wall,
                     "Wall (Drag)",
                 );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Portal,
+                    "Portal (Drag entry → exit)",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Portal {
+                    ui.add(
+                        egui::Slider::new(&mut self.state.editor_portal_rotation, -180.0..=180.0)
+                            .step_by(15.0)
+                            .text("Exit turn (°)"),
+                    );
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Field,
+                    "Force Field",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Field {
+                    ui.horizontal(|ui| {
+                        for kind in crate::game::map_file::FieldKind::ALL {
+                            ui.selectable_value(
+                                &mut self.state.editor_field_kind,
+                                kind,
+                                kind.name(),
+                            );
+                        }
+                    });
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Zone,
+                    "Zone (Drag)",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Zone {
+                    ui.horizontal(|ui| {
+                        for kind in crate::game::map_file::ZoneKind::ALL {
+                            ui.selectable_value(
+                                &mut self.state.editor_zone_kind,
+                                kind,
+                                kind.name(),
+                            );
+                        }
+                    });
+                    if self.state.editor_zone_kind.has_direction() {
+                        ui.add(
+                            egui::Slider::new(&mut self.state.editor_zone_angle, -180.0..=180.0)
+                                .step_by(15.0)
+                                .text("Direction (°)"),
+                        );
+                    }
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Platform,
+                    "Moving Platform",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Platform {
+                    use crate::game::PlatformTool;
+                    ui.horizontal(|ui| {
+                        let tool = &mut self.state.editor_platform_tool;
+                        ui.selectable_value(tool, PlatformTool::Slider, "Slider (Drag)");
+                        ui.selectable_value(tool, PlatformTool::Orbit, "Orbit (Drag)");
+                        ui.selectable_value(tool, PlatformTool::Waypoints, "Waypoints (Click)");
+                    });
+                    if self.state.editor_platform_tool == PlatformTool::Waypoints {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.state.editor_waypoints_looped, "Loop");
+                            if ui
+                                .add_enabled(
+                                    self.state.editor_waypoints.len() >= 2,
+                                    egui::Button::new("Finish Path"),
+                                )
+                                .clicked()
+                            {
+                                self.state.editor_finish_waypoints();
+                            }
+                            if ui.button("Clear").clicked() {
+                                self.state.editor_waypoints.clear();
+                            }
+                        });
+                    }
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Gate,
+                    "Timed Gate (Drag)",
+                );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Block,
+                    "Breakable Block",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Block {
+                    ui.add(
+                        egui::Slider::new(&mut self.state.editor_block_hits, 1..=10).text("Hits"),
+                    );
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::OneWayGate,
+                    "One-Way Gate (Drag)",
+                );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::DropTargets,
+                    "Drop Targets",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::DropTargets {
+                    ui.add(
+                        egui::Slider::new(&mut self.state.editor_drop_count, 1..=6).text("Targets"),
+                    );
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Splitter,
+                    "Multiball Splitter",
+                );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Joint,
+                    "Ropes & Swings",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Joint {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(
+                            &mut self.state.editor_joint_tool,
+                            crate::game::JointTool::Rope,
+                            "Rope",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.editor_joint_tool,
+                            crate::game::JointTool::Chain,
+                            "Chain (Drag)",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.editor_joint_tool,
+                            crate::game::JointTool::Pendulum,
+                            "Pendulum",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.editor_joint_tool,
+                            crate::game::JointTool::Wheel,
+                            "Wheel",
+                        );
+                    });
+                    if self.state.editor_joint_tool == crate::game::JointTool::Pendulum {
+                        ui.checkbox(&mut self.state.editor_pendulum_motor, "Motor (full turns)");
+                    }
+                }
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Tulip,
+                    "Tulip (Drag to its Trigger)",
+                );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Link,
+                    "Logic Link (Drag)",
+                );
+                ui.radio_value(
+                    &mut self.state.selected_tool,
+                    crate::game::EditorTool::Trap,
+                    "Trap",
+                );
+                if self.state.selected_tool == crate::game::EditorTool::Trap {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(
+                            &mut self.state.editor_trap_tool,
+                            crate::game::TrapTool::Pit,
+                            "Pit",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.editor_trap_tool,
+                            crate::game::TrapTool::Laser,
+                            "Laser (Drag)",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.editor_trap_tool,
+                            crate::game::TrapTool::Crusher,
+                            "Crusher",
+                        );
+                    });
+                }
                 ui.radio_value(
                     &mut self.state.selected_tool,
                     crate::game::EditorTool::Eraser,
@@ -116,7 +901,7 @@ impl eframe::App for PinballApp {
                 );
                 ui.checkbox(&mut self.state.editor_grid_snap, "Grid Snap");
 
-                ui.label(egui::RichText::new("Drag to create walls.").small());
+                ui.label(egui::RichText::new("Drag to create walls, portals and zones.").small());
             }
 
             ui.separator();
@@ -131,11 +916,85 @@ impl eframe::App for PinballApp {
                 crate::game::WinningCondition::Last,
                 "Last to Arrive",
             );
+            ui.radio_value(
+                &mut self.state.winning_condition,
+                crate::game::WinningCondition::LastStanding,
+                "Last Ball Standing",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Split balls count:");
+                ui.selectable_value(
+                    &mut self.state.clone_rank,
+                    crate::game::CloneRank::Best,
+                    "Best",
+                );
+                ui.selectable_value(
+                    &mut self.state.clone_rank,
+                    crate::game::CloneRank::Worst,
+                    "Worst",
+                );
+            });
+
+            ui.separator();
+            ui.label("Photo Finish:");
+            ui.checkbox(&mut self.state.slowmo_enabled, "Slow-motion near the goal");
+            ui.add(
+                egui::Slider::new(&mut self.state.photo_finish_gap, 0.01..=0.5)
+                    .text("Close gap (s)"),
+            );
 
             ui.separator();
             ui.label(format!("Balls Active: {}", self.state.balls.len()));
             ui.label(format!("Finished: {}", self.state.finished_balls.len()));
 
+            if !self.state.standings.is_empty() {
+                ui.separator();
+                match self.state.winning_condition {
+                    crate::game::WinningCondition::First => ui.label("Live Standings:"),
+                    crate::game::WinningCondition::Last
+                    | crate::game::WinningCondition::LastStanding => {
+                        ui.label("Live Standings (top = in danger):")
+                    }
+                };
+                egui::ScrollArea::vertical()
+                    .id_salt("live_standings")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (i, standing) in self.state.standings.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{}.", standing.position));
+
+                                // Position change arrow
+                                if standing.show_trend(time) {
+                                    if standing.trend > 0 {
+                                        ui.colored_label(egui::Color32::GREEN, "▲");
+                                    } else {
+                                        ui.colored_label(egui::Color32::RED, "▼");
+                                    }
+                                } else {
+                                    ui.label(" ");
+                                }
+
+                                let color = egui::Color32::from_rgb(
+                                    standing.color[0],
+                                    standing.color[1],
+                                    standing.color[2],
+                                );
+                                let mut text = egui::RichText::new(&standing.name).color(color);
+                                if i == 0 {
+                                    text = text.strong();
+                                }
+                                ui.label(text);
+                                ui.label(
+                                    egui::RichText::new(format!("{:.0}", standing.remaining))
+                                        .small()
+                                        .weak(),
+                                );
+                            });
+                        }
+                    });
+            }
+
             if !self.state.finished_balls.is_empty() {
                 ui.separator();
                 ui.label("Results:");
@@ -145,6 +1004,15 @@ impl eframe::App for PinballApp {
                         for (i, ball) in self.state.finished_balls.iter().enumerate() {
                             ui.horizontal(|ui| {
                                 ui.label(format!("{}.", i + 1));
+                                match ball.outcome {
+                                    crate::game::Outcome::Finished => {}
+                                    crate::game::Outcome::Eliminated => {
+                                        ui.colored_label(egui::Color32::RED, "✖");
+                                    }
+                                    crate::game::Outcome::Survived => {
+                                        ui.colored_label(egui::Color32::GOLD, "★");
+                                    }
+                                }
 
                                 // Animation Logic
                                 let time = ctx.input(|i| i.time);
@@ -173,31 +1041,19 @@ impl eframe::App for PinballApp {
                                     ui.ctx().request_repaint(); // Continue animation
                                 }
 
-                                ui.label(egui::RichText::new(&ball.name).size(size).color(color));
+                                let mut text =
+                                    egui::RichText::new(&ball.name).size(size).color(color);
+                                if ball.outcome == crate::game::Outcome::Eliminated {
+                                    text = text.strikethrough().weak();
+                                }
+                                ui.label(text);
                             });
                         }
                     });
 
                 // Show Winner
                 ui.separator();
-                let winner_idx = match self.state.winning_condition {
-                    crate::game::WinningCondition::First => {
-                        if !self.state.finished_balls.is_empty() {
-                            Some(0)
-                        } else {
-                            None
-                        }
-                    }
-                    crate::game::WinningCondition::Last => {
-                        if self.state.balls.is_empty() && !self.state.finished_balls.is_empty() {
-                            Some(self.state.finished_balls.len() - 1)
-                        } else {
-                            None
-                        }
-                    }
-                };
-
-                if let Some(idx) = winner_idx {
+                if let Some(idx) = self.state.winner() {
                     let winner = &self.state.finished_balls[idx];
                     ui.label(
                         egui::RichText::new(format!("WINNER: {}", winner.name))
@@ -206,9 +1062,45 @@ impl eframe::App for PinballApp {
                             .color(egui::Color32::GREEN),
                     );
                 }
+                if self.state.race_finished() {
+                    self.export_buttons(ui);
+                }
+            }
+
+            ui.separator();
+            if let Some(status) =
+                crate::ui::history_panel::show(ui, &mut self.history, &mut self.history_view)
+            {
+                self.status = Some(status);
+            }
+            if let Some(action) = crate::ui::tournament_panel::show(
+                ui,
+                self.tournament.as_ref(),
+                &mut self.tournament_view,
+            ) {
+                self.tournament_action(action);
+            }
+            if let Some(status) =
+                crate::ui::audit_panel::show(ui, &self.audit, &mut self.audit_view)
+            {
+                self.status = Some(status);
             }
         });
 
+        if self.verify_view.open {
+            crate::ui::verify_panel::show(ctx, &mut self.verify_view, &self.state.map);
+        }
+
+        // Photo Finish (once the race is over)
+        if self.state.balls.is_empty() && !self.state.finished_balls.is_empty() {
+            crate::ui::photo_finish::show(
+                ctx,
+                &self.state,
+                &mut self.show_photo_finish,
+                &mut self.photo_finish_index,
+            );
+        }
+
         // Main Canvas
         egui::CentralPanel::default().show(ctx, |ui| {
             let (response, painter) =
@@ -262,6 +1154,16 @@ impl eframe::App for PinballApp {
                         // OR if click matches simple tool.
                         // For simplicity, let's trigger start/end on click if not dragging.
                         if self.state.selected_tool == crate::game::EditorTool::Pin
+                            || self.state.selected_tool == crate::game::EditorTool::Field
+                            || self.state.selected_tool == crate::game::EditorTool::Platform
+                            || self.state.selected_tool == crate::game::EditorTool::Block
+                            || self.state.selected_tool == crate::game::EditorTool::DropTargets
+                            || self.state.selected_tool == crate::game::EditorTool::Splitter
+                            || self.state.selected_tool == crate::game::EditorTool::Tulip
+                            || self.state.selected_tool == crate::game::EditorTool::Joint
+                                && self.state.editor_joint_tool != crate::game::JointTool::Chain
+                            || self.state.selected_tool == crate::game::EditorTool::Trap
+                                && self.state.editor_trap_tool != crate::game::TrapTool::Laser
                             || self.state.selected_tool == crate::game::EditorTool::Eraser
                         {
                             self.state.editor_input_start(wx, wy);
@@ -269,9 +1171,57 @@ impl eframe::App for PinballApp {
                         }
                     }
 
-                    // Preview for Wall Dragging
+                    // Preview for Wall / Portal Dragging
                     if let Some((sx, sy)) = self.state.editor_drag_start {
-                        if self.state.selected_tool == crate::game::EditorTool::Wall {
+                        if self.state.selected_tool == crate::game::EditorTool::Portal {
+                            let radius = crate::game::maps::PORTAL_RADIUS;
+                            let stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+                            let start_screen = to_screen(sx, sy);
+                            painter.circle_stroke(start_screen, radius, stroke);
+                            painter.circle_stroke(pos, radius, stroke);
+                            painter.extend(egui::Shape::dashed_line(
+                                &[start_screen, pos],
+                                egui::Stroke::new(1.0, egui::Color32::YELLOW),
+                                6.0,
+                                4.0,
+                            ));
+                        }
+                        if self.state.selected_tool == crate::game::EditorTool::Tulip
+                            || self.state.selected_tool == crate::game::EditorTool::Link
+                        {
+                            painter.extend(egui::Shape::dashed_line(
+                                &[to_screen(sx, sy), pos],
+                                egui::Stroke::new(1.5, egui::Color32::YELLOW),
+                                6.0,
+                                4.0,
+                            ));
+                        }
+                        if self.state.selected_tool == crate::game::EditorTool::Zone {
+                            painter.rect_stroke(
+                                egui::Rect::from_two_pos(to_screen(sx, sy), pos),
+                                0.0,
+                                egui::Stroke::new(2.0, egui::Color32::YELLOW),
+                            );
+                        }
+                        if self.state.selected_tool == crate::game::EditorTool::Platform
+                            && self.state.editor_platform_tool == crate::game::PlatformTool::Orbit
+                        {
+                            let start_screen = to_screen(sx, sy);
+                            painter.circle_stroke(
+                                start_screen,
+                                start_screen.distance(pos),
+                                egui::Stroke::new(2.0, egui::Color32::YELLOW),
+                            );
+                        }
+                        if self.state.selected_tool == crate::game::EditorTool::Wall
+                            || self.state.selected_tool == crate::game::EditorTool::Gate
+                            || self.state.selected_tool == crate::game::EditorTool::OneWayGate
+                            || self.state.selected_tool == crate::game::EditorTool::Trap
+                            || self.state.selected_tool == crate::game::EditorTool::Joint
+                            || self.state.selected_tool == crate::game::EditorTool::Platform
+                                && self.state.editor_platform_tool
+                                    == crate::game::PlatformTool::Slider
+                        {
                             // Snap current mouse pos for preview
                             // We don't have access to snap function here easily unless exposed,
                             // but we can trust GameState handles actual creation.
@@ -291,6 +1241,9 @@ impl eframe::App for PinballApp {
             // Ideally we iterate colliders and draw them.
             // For now, let's just cheat and draw the known map boundaries or iterate if we can exposed iter
 
+            // Zones sit underneath everything
+            crate::ui::map_objects::paint_zones(&painter, &self.state, time, &to_screen);
+
             // Let's iterate the collider set in physics
             for (handle, collider) in self.state.physics.collider_set.iter() {
                 let translation = collider.translation();
@@ -319,35 +1272,87 @@ impl eframe::App for PinballApp {
                     egui::Color32::from_rgb(r, g, b)
                 };
 
+                // Pits, lasers and tulip pockets are drawn by map_objects
+                if collider.is_sensor()
+                    && (collider.user_data == crate::game::maps::TRAP
+                        || collider.user_data == crate::game::maps::TULIP_POCKET)
+                {
+                    continue;
+                }
+
+                // Sunk drop targets: just a faint outline where they were
+                if !collider.is_enabled() {
+                    if let Some(cuboid) = shape.as_cuboid() {
+                        let hx = cuboid.half_extents.x;
+                        let hy = cuboid.half_extents.y;
+                        let transform = collider.position();
+                        let mut points: Vec<egui::Pos2> = [
+                            point![-hx, -hy],
+                            point![hx, -hy],
+                            point![hx, hy],
+                            point![-hx, hy],
+                        ]
+                        .iter()
+                        .map(|p| {
+                            let world_p = transform * p;
+                            to_screen(world_p.x, world_p.y)
+                        })
+                        .collect();
+                        points.push(points[0]);
+                        painter.add(egui::Shape::line(
+                            points,
+                            egui::Stroke::new(1.0, egui::Color32::from_gray(70)),
+                        ));
+                    }
+                    continue;
+                }
+
+                // Sensors aren't solid: draw them as a translucent zone, not a wall
+                if collider.is_sensor() {
+                    if let Some(cuboid) = shape.as_cuboid() {
+                        let hx = cuboid.half_extents.x;
+                        let hy = cuboid.half_extents.y;
+                        let transform = collider.position();
+                        let points: Vec<egui::Pos2> = [
+                            point![-hx, -hy],
+                            point![hx, -hy],
+                            point![hx, hy],
+                            point![-hx, hy],
+                        ]
+                        .iter()
+                        .map(|p| {
+                            let world_p = transform * p;
+                            to_screen(world_p.x, world_p.y)
+                        })
+                        .collect();
+
+                        // Goal, or a one-way gate still open
+                        let color = palette::collider(collider.user_data)
+                            .map_or(egui::Color32::GRAY, palette::rgb);
+                        let pulse = ((time * 3.0).sin() * 0.5 + 0.5) as f32;
+                        let fill = egui::Color32::from_rgba_unmultiplied(
+                            color.r(),
+                            color.g(),
+                            color.b(),
+                            (30.0 + 40.0 * pulse) as u8,
+                        );
+                        painter.add(egui::Shape::convex_polygon(
+                            points,
+                            fill,
+                            egui::Stroke::new(1.0, color),
+                        ));
+                    }
+                    continue;
+                }
+
                 // Check shape type
                 if let Some(ball) = shape.as_ball() {
                     let radius = ball.radius;
-                    // Color logic
-                    let mut is_event = false;
-                    let mut shape_id = 0;
-
-                    let color = if (collider.user_data >> 64) & 1 == 1 {
-                        is_event = true;
-                        let r = (collider.user_data >> 48) as u8;
-                        let g = (collider.user_data >> 40) as u8;
-                        let b = (collider.user_data >> 32) as u8;
-                        shape_id = collider.user_data as u8;
-                        egui::Color32::from_rgb(r, g, b)
-                    } else if collider.user_data == 11 {
-                        egui::Color32::from_rgb(0, 100, 255) // Level 1: Blue
-                    } else if collider.user_data == 12 {
-                        egui::Color32::from_rgb(50, 255, 50) // Level 2: Green
-                    } else if collider.user_data == 13 {
-                        egui::Color32::from_rgb(255, 255, 0) // Level 3: Yellow
-                    } else if collider.user_data == 14 {
-                        egui::Color32::from_rgb(255, 165, 0) // Level 4: Orange
-                    } else if collider.user_data == 15 {
-                        egui::Color32::from_rgb(255, 50, 50) // Level 5: Red
-                    } else if collider.user_data == 99 {
-                        egui::Color32::from_rgb(0, 255, 255)
-                    } else {
-                        egui::Color32::GRAY
-                    };
+                    // Event obstacles carry their shape in the low byte
+                    let is_event = (collider.user_data >> 64) & 1 == 1;
+                    let shape_id = collider.user_data as u8;
+                    let color = palette::collider(collider.user_data)
+                        .map_or(egui::Color32::GRAY, palette::rgb);
 
                     let color = apply_flash(color, flash_factor);
                     let radius = radius * (1.0 + flash_factor * 0.3);
@@ -401,36 +1406,8 @@ impl eframe::App for PinballApp {
                     let rotation = collider.rotation();
                     let angle = rotation.angle();
 
-                    // Color logic based on user_data
-                    // Color logic based on user_data
-                    // Color logic based on user_data
-                    // Color logic based on user_data
-                    let color = if (collider.user_data >> 64) & 1 == 1 {
-                        let r = (collider.user_data >> 48) as u8;
-                        let g = (collider.user_data >> 40) as u8;
-                        let b = (collider.user_data >> 32) as u8;
-                        egui::Color32::from_rgb(r, g, b)
-                    } else if collider.user_data == 11 {
-                        egui::Color32::from_rgb(0, 100, 255) // Blue
-                    } else if collider.user_data == 12 {
-                        egui::Color32::from_rgb(50, 255, 50) // Green
-                    } else if collider.user_data == 13 {
-                        egui::Color32::from_rgb(255, 255, 0) // Yellow
-                    } else if collider.user_data == 14 {
-                        egui::Color32::from_rgb(255, 165, 0) // Orange
-                    } else if collider.user_data == 15 {
-                        egui::Color32::from_rgb(255, 50, 50) // Red
-                    } else if collider.user_data == 99 {
-                        egui::Color32::from_rgb(0, 255, 255) // Cyan for Goal
-                    } else if collider.user_data == 21 {
-                        egui::Color32::from_rgb(0, 255, 255) // Slow - Cyan
-                    } else if collider.user_data == 22 {
-                        egui::Color32::from_rgb(255, 0, 255) // Normal - Magenta
-                    } else if collider.user_data == 23 {
-                        egui::Color32::from_rgb(128, 0, 128) // Fast - Purple
-                    } else {
-                        egui::Color32::DARK_GRAY
-                    };
+                    let color = palette::collider(collider.user_data)
+                        .map_or(egui::Color32::DARK_GRAY, palette::rgb);
 
                     let color = apply_flash(color, flash_factor);
                     // Pulse size for cuboids (scale points or rect) - hard for rect.
@@ -505,16 +1482,23 @@ impl eframe::App for PinballApp {
                             egui::Stroke::new(2.0, glow_color),
                         ));
                     }
+                } else if let Some(capsule) = shape.as_capsule() {
+                    // Rope and chain links, pendulum arms
+                    let color = palette::collider(collider.user_data)
+                        .map_or(egui::Color32::DARK_GRAY, palette::rgb);
+                    let color = apply_flash(color, flash_factor);
+                    let transform = collider.position();
+                    let a = transform * capsule.segment.a;
+                    let b = transform * capsule.segment.b;
+                    let a = to_screen(a.x, a.y);
+                    let b = to_screen(b.x, b.y);
+                    painter.line_segment([a, b], egui::Stroke::new(capsule.radius * 2.0, color));
+                    painter.circle_filled(a, capsule.radius, color);
+                    painter.circle_filled(b, capsule.radius, color);
                 } else if let Some(tri) = shape.as_triangle() {
                     // Triangle Rendering
-                    let color = if (collider.user_data >> 64) & 1 == 1 {
-                        let r = (collider.user_data >> 48) as u8;
-                        let g = (collider.user_data >> 40) as u8;
-                        let b = (collider.user_data >> 32) as u8;
-                        egui::Color32::from_rgb(r, g, b)
-                    } else {
-                        egui::Color32::YELLOW // Fallback
-                    };
+                    let color = palette::collider(collider.user_data)
+                        .map_or(egui::Color32::YELLOW, palette::rgb);
                     let color = apply_flash(color, flash_factor);
 
                     let a = tri.a;
@@ -542,6 +1526,20 @@ impl eframe::App for PinballApp {
                 }
             }
 
+            // Portals and other sensor-based objects
+            crate::ui::map_objects::paint(&painter, &self.state, time, &to_screen);
+
+            // Slow-motion Indicator
+            if self.state.is_running && self.state.slowmo < 0.9 {
+                painter.text(
+                    rect.left_top() + egui::vec2(10.0, 10.0),
+                    egui::Align2::LEFT_TOP,
+                    "SLOW-MO",
+                    egui::FontId::proportional(18.0),
+                    egui::Color32::from_rgb(255, 80, 200),
+                );
+            }
+
             // Particles
             for p in &self.state.particles {
                 let pos = to_screen(p.x, p.y);
@@ -554,6 +1552,8 @@ impl eframe::App for PinballApp {
             }
 
             // Draw Balls
+            let highlighted = self.state.highlighted_ball();
+            let ball_radius = self.state.map.physics.ball_radius;
             for ball in &self.state.balls {
                 let ball_handle = ball.handle;
                 if let Some(rb) = self.state.physics.rigid_body_set.get(ball_handle) {
@@ -562,6 +1562,30 @@ impl eframe::App for PinballApp {
                     let color =
                         egui::Color32::from_rgb(ball.color[0], ball.color[1], ball.color[2]);
 
+                    // Leader (First) / In Danger (Last) Highlight
+                    if highlighted == Some(ball_handle) {
+                        let pulse = ((time * 6.0).sin() * 0.5 + 0.5) as f32;
+                        let (ring_color, tag) = match self.state.winning_condition {
+                            crate::game::WinningCondition::First => (egui::Color32::GOLD, "LEADER"),
+                            crate::game::WinningCondition::Last
+                            | crate::game::WinningCondition::LastStanding => {
+                                (egui::Color32::RED, "DANGER")
+                            }
+                        };
+                        painter.circle_stroke(
+                            screen_pos,
+                            ball_radius + 6.0 + pulse * 4.0,
+                            egui::Stroke::new(2.5, ring_color),
+                        );
+                        painter.text(
+                            screen_pos - egui::vec2(0.0, ball_radius + 10.0 + pulse * 4.0),
+                            egui::Align2::CENTER_BOTTOM,
+                            tag,
+                            egui::FontId::proportional(10.0),
+                            ring_color,
+                        );
+                    }
+
                     // Ball Glow
                     let glow_color = egui::Color32::from_rgba_unmultiplied(
                         ball.color[0],
@@ -569,14 +1593,22 @@ impl eframe::App for PinballApp {
                         ball.color[2],
                         128,
                     );
-                    painter.circle_filled(screen_pos, 12.0, glow_color);
+                    painter.circle_filled(screen_pos, ball_radius * 1.5, glow_color);
 
                     painter.circle(
                         screen_pos,
-                        8.0,
+                        ball_radius,
                         color,
                         egui::Stroke::new(1.5, egui::Color32::WHITE), // Bright Outline
                     );
+                    crate::ui::skins::paint(
+                        &painter,
+                        &mut self.skin_textures,
+                        screen_pos,
+                        ball_radius,
+                        ball,
+                        &self.state.owners[ball.owner as usize],
+                    );
 
                     // Adaptive Text Color
                     let text_color = if ui.visuals().dark_mode {
@@ -586,7 +1618,7 @@ impl eframe::App for PinballApp {
                     };
 
                     // Label
-                    let text_pos = screen_pos + egui::vec2(0.0, 12.0);
+                    let text_pos = screen_pos + egui::vec2(0.0, ball_radius * 1.5);
                     painter.text(
                         text_pos,
                         egui::Align2::CENTER_TOP,
@@ -596,6 +1628,11 @@ impl eframe::App for PinballApp {
                     );
                 }
             }
+
+            // Physics Debug Overlay
+            if self.debug_overlay {
+                crate::ui::debug_overlay::paint(&painter, rect, &self.state, &to_screen);
+            }
         });
     }
 }
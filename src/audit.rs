@@ -0,0 +1,260 @@
+//! Append-only, hash-chained log of every run: when it started (and under
+//! which commitment), and how it ended, with the seed and results. Each
+//! record carries the hash of the one before it, so editing or deleting a
+//! record breaks the chain from there on.
+//!
+//! Cutting records off the end leaves a valid, shorter chain; compare the
+//! head hash with one noted down (or exported) earlier to catch that.
+
+use crate::history::format_date;
+use crate::proof::sha256_hex;
+use serde::{Deserialize, Serialize};
+
+/// `prev_hash` of the first record.
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    Started,  // Balls dropped; only the commitment is known
+    Aborted,  // Stopped (or replaced by a new run) before the finish
+    Finished, // Ran to the end
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Started => "started",
+            Event::Aborted => "aborted",
+            Event::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub index: u64,
+    pub timestamp: u64, // Unix seconds
+    pub event: Event,
+    pub roster: String,
+    pub commitment: String,   // `proof::Inputs::commitment` of the run
+    pub seed: Option<u64>,    // Revealed once the run is over (unknown if the app closed mid-run)
+    pub results: Vec<String>, // Finished runs: one name per person, best first
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl Record {
+    /// Hash of everything but `hash` itself.
+    fn digest(&self) -> String {
+        let seed = self.seed.map(|s| s.to_string()).unwrap_or_default();
+        let text = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.index,
+            self.timestamp,
+            self.event.name(),
+            self.roster,
+            self.commitment,
+            seed,
+            self.results.join("\t"),
+            self.prev_hash
+        );
+        sha256_hex(&text)
+    }
+}
+
+/// Where `AuditLog::verify` found the chain broken.
+pub struct Break {
+    pub index: usize,
+    pub reason: &'static str,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    pub records: Vec<Record>, // Oldest first; only ever appended to
+}
+
+impl AuditLog {
+    pub fn append(
+        &mut self,
+        event: Event,
+        roster: &str,
+        commitment: &str,
+        seed: Option<u64>,
+        results: Vec<String>,
+    ) {
+        let mut record = Record {
+            index: self.records.len() as u64,
+            timestamp: crate::history::now(),
+            event,
+            roster: roster.to_owned(),
+            commitment: commitment.to_owned(),
+            seed,
+            results,
+            prev_hash: self.head().to_owned(),
+            hash: String::new(),
+        };
+        record.hash = record.digest();
+        self.records.push(record);
+    }
+
+    /// Hash of the newest record, which vouches for the whole chain.
+    pub fn head(&self) -> &str {
+        self.records.last().map_or(GENESIS, |r| r.hash.as_str())
+    }
+
+    /// The run still in progress (or cut off by closing the app), if any.
+    pub fn open_run(&self) -> Option<&Record> {
+        self.records.last().filter(|r| r.event == Event::Started)
+    }
+
+    /// Runs aborted since the last finished one, before record `i`.
+    pub fn aborts_before(&self, i: usize) -> usize {
+        self.records[..i.min(self.records.len())]
+            .iter()
+            .rev()
+            .take_while(|r| r.event != Event::Finished)
+            .filter(|r| r.event == Event::Aborted)
+            .count()
+    }
+
+    /// Check every record's hash and link. Records after the first break
+    /// can't be trusted either.
+    pub fn verify(&self) -> Result<(), Break> {
+        let mut prev = GENESIS;
+        for (i, record) in self.records.iter().enumerate() {
+            if record.index != i as u64 {
+                return Err(Break {
+                    index: i,
+                    reason: "out of sequence: records were removed or reordered",
+                });
+            }
+            if record.prev_hash != prev {
+                return Err(Break {
+                    index: i,
+                    reason: "doesn't follow the record before it: a record was removed",
+                });
+            }
+            if record.digest() != record.hash {
+                return Err(Break {
+                    index: i,
+                    reason: "contents don't match its hash: the record was edited",
+                });
+            }
+            prev = &record.hash;
+        }
+        Ok(())
+    }
+
+    /// The whole log, for checking elsewhere (`--verify-log`).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("audit log serializes")
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))] // Only the CLI reads logs back
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Not an audit log: {}", e))
+    }
+}
+
+/// One line for `record`, e.g. "#12 2026-10-18 14:07 finished: Alice (seed 42)".
+pub fn describe(record: &Record) -> String {
+    let mut line = format!(
+        "#{} {} {}",
+        record.index,
+        format_date(record.timestamp),
+        record.event.name()
+    );
+    if let Some(winner) = record.results.first() {
+        line.push_str(&format!(": {}", winner));
+    }
+    if let Some(seed) = record.seed {
+        line.push_str(&format!(" (seed {})", seed));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A finished run, an aborted one and a second finished run.
+    fn sample() -> AuditLog {
+        let mut log = AuditLog::default();
+        log.append(Event::Started, "Team", "c1", None, Vec::new());
+        log.append(Event::Finished, "Team", "c1", Some(1), vec!["Alice".into()]);
+        log.append(Event::Started, "Team", "c2", None, Vec::new());
+        log.append(Event::Aborted, "Team", "c2", Some(2), Vec::new());
+        log.append(Event::Started, "Team", "c3", None, Vec::new());
+        log.append(Event::Finished, "Team", "c3", Some(3), vec!["Bob".into()]);
+        log
+    }
+
+    fn broken_at(log: &AuditLog) -> Option<(usize, &'static str)> {
+        log.verify().err().map(|b| (b.index, b.reason))
+    }
+
+    #[test]
+    fn appended_chain_verifies() {
+        let log = sample();
+        assert_eq!(broken_at(&log), None);
+        assert_eq!(log.records[0].prev_hash, GENESIS);
+        assert_eq!(log.head(), log.records[5].hash);
+        assert_eq!(broken_at(&AuditLog::default()), None);
+        assert_eq!(AuditLog::default().head(), GENESIS);
+    }
+
+    #[test]
+    fn edited_record_is_caught() {
+        let mut log = sample();
+        log.records[1].results = vec!["Mallory".into()];
+        let (index, reason) = broken_at(&log).unwrap();
+        assert_eq!(index, 1);
+        assert!(reason.contains("edited"));
+    }
+
+    #[test]
+    fn removed_record_is_caught() {
+        let mut log = sample();
+        log.records.remove(2);
+        assert_eq!(broken_at(&log).map(|(i, _)| i), Some(2));
+
+        // Renumbering the rest doesn't hide the gap
+        let mut log = sample();
+        log.records.remove(2);
+        for (i, record) in log.records.iter_mut().enumerate() {
+            record.index = i as u64;
+        }
+        let (index, reason) = broken_at(&log).unwrap();
+        assert_eq!(index, 2);
+        assert!(reason.contains("removed"));
+    }
+
+    #[test]
+    fn truncated_log_only_shows_in_the_head() {
+        let full = sample();
+        let mut cut = full.clone();
+        cut.records.truncate(4);
+        assert_eq!(broken_at(&cut), None);
+        assert_ne!(cut.head(), full.head());
+    }
+
+    #[test]
+    fn open_runs_and_aborts() {
+        let mut log = sample();
+        assert!(log.open_run().is_none());
+        assert_eq!(log.aborts_before(5), 1);
+        assert_eq!(log.aborts_before(2), 0);
+        log.append(Event::Started, "Team", "c4", None, Vec::new());
+        assert_eq!(log.open_run().map(|r| r.commitment.as_str()), Some("c4"));
+        assert_eq!(log.aborts_before(usize::MAX), 0);
+    }
+
+    #[test]
+    fn json_round_trip_keeps_the_chain() {
+        let log = sample();
+        let read = AuditLog::from_json(&log.to_json()).unwrap();
+        assert_eq!(broken_at(&read), None);
+        assert_eq!(read.head(), log.head());
+        assert!(AuditLog::from_json("[]").is_err());
+    }
+}
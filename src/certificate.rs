@@ -0,0 +1,398 @@
+//! A results certificate: the final board next to the winner and the ranked
+//! list, rasterized on the CPU so it works without a window (e.g. from the
+//! command line).
+
+use crate::export::Report;
+use crate::game::map_file::{MapElement, TrapKind};
+use crate::game::{maps, GameState};
+use crate::ui::palette;
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+use rapier2d::prelude::*;
+use std::collections::HashSet;
+
+const MARGIN: f32 = 20.0;
+const PANEL_WIDTH: f32 = 360.0;
+const BACKGROUND: [u8; 3] = [10, 10, 15];
+const PANEL: [u8; 3] = [22, 22, 32];
+const TEXT: [u8; 3] = [235, 235, 240];
+const DIM: [u8; 3] = [140, 140, 155];
+const GOLD: [u8; 3] = [255, 200, 40];
+const GRAY: [u8; 3] = [160, 160, 160]; // egui's GRAY / DARK_GRAY, the canvas fallbacks
+const DARK_GRAY: [u8; 3] = [96, 96, 96];
+
+/// PNG bytes of the certificate for the race in `state`.
+pub fn png(state: &GameState, report: &Report) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    render(state, report)
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| format!("Could not encode PNG: {}", e))?;
+    Ok(bytes)
+}
+
+pub fn render(state: &GameState, report: &Report) -> RgbaImage {
+    let font = FontRef::try_from_slice(crate::ui::FONT).expect("embedded font loads");
+    let board_w = state.map_width + MARGIN * 2.0;
+    let rows = report.ranking.len() as f32;
+    let height = (state.map_height + MARGIN * 2.0).max(260.0 + rows.min(40.0) * 24.0);
+    let mut canvas = Canvas {
+        image: RgbaImage::from_pixel(
+            (board_w + PANEL_WIDTH) as u32,
+            height as u32,
+            Rgba([BACKGROUND[0], BACKGROUND[1], BACKGROUND[2], 255]),
+        ),
+        font,
+    };
+    paint_board(&mut canvas, state);
+    paint_panel(&mut canvas, state, report, board_w);
+    canvas.image
+}
+
+/// The board as the canvas draws it, minus glow and animation: zones,
+/// colliders, then the objects drawn from the map (fields, portals, pits,
+/// lasers, tulip pockets), and any balls left.
+fn paint_board(canvas: &mut Canvas, state: &GameState) {
+    let center = [
+        MARGIN + state.map_width / 2.0,
+        MARGIN + state.map_height / 2.0,
+    ];
+    let to_screen = |p: Point<f32>| [center[0] + p.x, center[1] - p.y];
+    let balls: HashSet<RigidBodyHandle> = state.balls.iter().map(|b| b.handle).collect();
+
+    for object in &state.map.objects {
+        if let MapElement::Zone {
+            ref points, kind, ..
+        } = object.element
+        {
+            let points: Vec<[f32; 2]> = points
+                .iter()
+                .map(|p| to_screen(point![p[0], p[1]]))
+                .collect();
+            if points.len() >= 3 {
+                canvas.polygon(&points, palette::zone(kind), 0.2);
+            }
+        }
+    }
+
+    for (_, collider) in state.physics.collider_set.iter() {
+        if !collider.is_enabled() || collider.parent().is_some_and(|p| balls.contains(&p)) {
+            continue;
+        }
+        let shape = collider.shape();
+        // Sensors are drawn as translucent boxes (goal, open one-way gates);
+        // the rest come from the map objects below
+        let sensor = collider.is_sensor();
+        if sensor
+            && (shape.as_cuboid().is_none()
+                || collider.user_data == maps::TRAP
+                || collider.user_data == maps::TULIP_POCKET)
+        {
+            continue;
+        }
+        let fallback = if sensor || shape.as_ball().is_some() {
+            GRAY
+        } else if shape.as_triangle().is_some() {
+            [255, 255, 0]
+        } else {
+            DARK_GRAY
+        };
+        let color = palette::collider(collider.user_data).unwrap_or(fallback);
+        let alpha = if sensor { 0.35 } else { 1.0 };
+        let transform = collider.position();
+        if let Some(ball) = shape.as_ball() {
+            let c = to_screen(transform * Point::origin());
+            canvas.circle(c, ball.radius, color, alpha);
+        } else if let Some(cuboid) = shape.as_cuboid() {
+            let [hx, hy] = [cuboid.half_extents.x, cuboid.half_extents.y];
+            let corners = [
+                point![-hx, -hy],
+                point![hx, -hy],
+                point![hx, hy],
+                point![-hx, hy],
+            ];
+            let points: Vec<[f32; 2]> = corners.iter().map(|&p| to_screen(transform * p)).collect();
+            canvas.polygon(&points, color, alpha);
+        } else if let Some(capsule) = shape.as_capsule() {
+            let a = to_screen(transform * capsule.segment.a);
+            let b = to_screen(transform * capsule.segment.b);
+            canvas.capsule(a, b, capsule.radius, color, alpha);
+        } else if let Some(tri) = shape.as_triangle() {
+            let points = [tri.a, tri.b, tri.c].map(|p| to_screen(transform * p));
+            canvas.polygon(&points, color, alpha);
+        }
+    }
+
+    for object in &state.map.objects {
+        match object.element {
+            MapElement::Field {
+                x, y, radius, kind, ..
+            } => {
+                let c = to_screen(point![x, y]);
+                canvas.circle(c, radius, palette::field(kind), 0.06);
+                canvas.ring(c, radius, 1.0, palette::field(kind), 0.3);
+            }
+            MapElement::Portal {
+                entry_x,
+                entry_y,
+                exit_x,
+                exit_y,
+                radius,
+                ..
+            } => {
+                let color = crate::game::portal_color(object.id);
+                for c in [point![entry_x, entry_y], point![exit_x, exit_y]] {
+                    canvas.circle(to_screen(c), radius, color, 0.16);
+                    canvas.ring(to_screen(c), radius, 2.5, color, 1.0);
+                }
+            }
+            MapElement::Trap {
+                x,
+                y,
+                trap: TrapKind::Pit { radius },
+            } => {
+                let c = to_screen(point![x, y]);
+                canvas.circle(c, radius, palette::PIT, 1.0);
+                canvas.ring(c, radius, 2.0, palette::PIT_RIM, 1.0);
+            }
+            MapElement::Trap {
+                x,
+                y,
+                trap:
+                    TrapKind::Laser {
+                        length,
+                        angle,
+                        on,
+                        off,
+                    },
+            } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let (hx, hy) = (cos * length / 2.0, sin * length / 2.0);
+                let a = to_screen(point![x - hx, y - hy]);
+                let b = to_screen(point![x + hx, y + hy]);
+                let lit = crate::game::motion::laser_on(on, off, state.sim_time);
+                let alpha = if lit { 1.0 } else { 0.3 };
+                canvas.capsule(a, b, 1.0, palette::TRAP_RED, alpha);
+            }
+            MapElement::Tulip { x, y, .. } => {
+                let [pocket, _] = maps::tulip_pocket(x, y);
+                let c = to_screen(point![pocket.x, pocket.y]);
+                canvas.circle(c, 10.0, palette::TULIP, 0.16);
+            }
+            _ => {}
+        }
+    }
+
+    // Balls still on the board (a race cut short)
+    let radius = state.map.physics.ball_radius;
+    for ball in &state.balls {
+        if let Some(rb) = state.physics.rigid_body_set.get(ball.handle) {
+            let c = to_screen(Point::from(*rb.translation()));
+            canvas.circle(c, radius, ball.color, 1.0);
+        }
+    }
+}
+
+fn paint_panel(canvas: &mut Canvas, state: &GameState, report: &Report, x: f32) {
+    let height = canvas.image.height() as f32;
+    canvas.rect([x, 0.0], [x + PANEL_WIDTH, height], PANEL);
+    let x = x + 20.0;
+    let mut y = 20.0;
+
+    // Winner banner
+    canvas.rect(
+        [x - 4.0, y],
+        [x + PANEL_WIDTH - 36.0, y + 72.0],
+        [60, 48, 10],
+    );
+    canvas.text([x + 8.0, y + 6.0], 16.0, "WINNER", GOLD);
+    let winner = report.winner.as_deref().unwrap_or("No winner");
+    let color = state
+        .winner()
+        .map(|i| state.finished_balls[i].color)
+        .unwrap_or(TEXT);
+    canvas.text([x + 8.0, y + 28.0], 34.0, winner, color);
+    y += 90.0;
+
+    for line in [
+        format!("{} — {}", report.map, report.condition),
+        format!("{} UTC", report.date),
+        format!("Seed {}", report.seed),
+        format!("Map {}", &report.map_id[..12.min(report.map_id.len())]),
+    ] {
+        canvas.text([x, y], 15.0, &line, DIM);
+        y += 20.0;
+    }
+    y += 12.0;
+
+    let colors: Vec<[u8; 3]> = state
+        .ranking()
+        .into_iter()
+        .map(|i| state.finished_balls[i].color)
+        .collect();
+    let room = ((height - y - 30.0) / 24.0).max(1.0) as usize;
+    for (i, (placing, color)) in report.ranking.iter().zip(colors).take(room).enumerate() {
+        let out = placing.eliminated;
+        canvas.text([x, y], 18.0, &format!("{}.", i + 1), DIM);
+        canvas.circle(
+            [x + 44.0, y + 11.0],
+            7.0,
+            color,
+            if out { 0.4 } else { 1.0 },
+        );
+        canvas.text(
+            [x + 60.0, y],
+            18.0,
+            &placing.name,
+            if out { DIM } else { TEXT },
+        );
+        y += 24.0;
+    }
+    if report.ranking.len() > room {
+        let more = format!("… and {} more", report.ranking.len() - room);
+        canvas.text([x, y], 15.0, &more, DIM);
+    }
+}
+
+struct Canvas<'a> {
+    image: RgbaImage,
+    font: FontRef<'a>,
+}
+
+impl Canvas<'_> {
+    fn blend(&mut self, x: i32, y: i32, color: [u8; 3], alpha: f32) {
+        if x < 0 || y < 0 || x >= self.image.width() as i32 || y >= self.image.height() as i32 {
+            return;
+        }
+        let alpha = alpha.clamp(0.0, 1.0);
+        let pixel = self.image.get_pixel_mut(x as u32, y as u32);
+        for i in 0..3 {
+            let old = pixel[i] as f32;
+            pixel[i] = (old + (color[i] as f32 - old) * alpha).round() as u8;
+        }
+    }
+
+    /// Fill where `distance` (signed, negative inside) is below zero, with a
+    /// one-pixel soft edge, over the box `min`..`max`.
+    fn fill(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        color: [u8; 3],
+        alpha: f32,
+        distance: impl Fn(f32, f32) -> f32,
+    ) {
+        for y in (min[1].floor() as i32 - 1)..=(max[1].ceil() as i32 + 1) {
+            for x in (min[0].floor() as i32 - 1)..=(max[0].ceil() as i32 + 1) {
+                let coverage = (0.5 - distance(x as f32 + 0.5, y as f32 + 0.5)).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend(x, y, color, coverage * alpha);
+                }
+            }
+        }
+    }
+
+    fn rect(&mut self, min: [f32; 2], max: [f32; 2], color: [u8; 3]) {
+        for y in min[1] as i32..max[1] as i32 {
+            for x in min[0] as i32..max[0] as i32 {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    fn circle(&mut self, c: [f32; 2], r: f32, color: [u8; 3], alpha: f32) {
+        self.fill(
+            [c[0] - r, c[1] - r],
+            [c[0] + r, c[1] + r],
+            color,
+            alpha,
+            |x, y| ((x - c[0]).powi(2) + (y - c[1]).powi(2)).sqrt() - r,
+        );
+    }
+
+    /// A circle outline `width` wide.
+    fn ring(&mut self, c: [f32; 2], r: f32, width: f32, color: [u8; 3], alpha: f32) {
+        let outer = r + width / 2.0;
+        self.fill(
+            [c[0] - outer, c[1] - outer],
+            [c[0] + outer, c[1] + outer],
+            color,
+            alpha,
+            |x, y| (((x - c[0]).powi(2) + (y - c[1]).powi(2)).sqrt() - r).abs() - width / 2.0,
+        );
+    }
+
+    fn capsule(&mut self, a: [f32; 2], b: [f32; 2], r: f32, color: [u8; 3], alpha: f32) {
+        let min = [a[0].min(b[0]) - r, a[1].min(b[1]) - r];
+        let max = [a[0].max(b[0]) + r, a[1].max(b[1]) + r];
+        let ab = [b[0] - a[0], b[1] - a[1]];
+        let length_sq = (ab[0] * ab[0] + ab[1] * ab[1]).max(f32::EPSILON);
+        self.fill(min, max, color, alpha, |x, y| {
+            let ap = [x - a[0], y - a[1]];
+            let t = ((ap[0] * ab[0] + ap[1] * ab[1]) / length_sq).clamp(0.0, 1.0);
+            ((ap[0] - ab[0] * t).powi(2) + (ap[1] - ab[1] * t).powi(2)).sqrt() - r
+        });
+    }
+
+    /// A convex polygon, either winding.
+    fn polygon(&mut self, points: &[[f32; 2]], color: [u8; 3], alpha: f32) {
+        let n = points.len();
+        let area: f32 = (0..n)
+            .map(|i| {
+                let (p, q) = (points[i], points[(i + 1) % n]);
+                p[0] * q[1] - q[0] * p[1]
+            })
+            .sum();
+        let sign = if area < 0.0 { -1.0 } else { 1.0 };
+        // Outward unit normals
+        let edges: Vec<([f32; 2], [f32; 2])> = (0..n)
+            .map(|i| {
+                let (p, q) = (points[i], points[(i + 1) % n]);
+                let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+                let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+                (p, [sign * dy / length, -sign * dx / length])
+            })
+            .collect();
+        let min = points
+            .iter()
+            .fold([f32::MAX; 2], |m, p| [m[0].min(p[0]), m[1].min(p[1])]);
+        let max = points
+            .iter()
+            .fold([f32::MIN; 2], |m, p| [m[0].max(p[0]), m[1].max(p[1])]);
+        self.fill(min, max, color, alpha, |x, y| {
+            edges
+                .iter()
+                .map(|(p, normal)| (x - p[0]) * normal[0] + (y - p[1]) * normal[1])
+                .fold(f32::MIN, f32::max)
+        });
+    }
+
+    /// `text` with its top-left corner at `at`.
+    fn text(&mut self, at: [f32; 2], size: f32, text: &str, color: [u8; 3]) {
+        let scale = PxScale::from(size);
+        let font = self.font.clone();
+        let scaled = font.as_scaled(scale);
+        let baseline = at[1] + scaled.ascent();
+        let mut caret = at[0];
+        let mut previous = None;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            previous = Some(id);
+            let glyph = id.with_scale_and_position(scale, ab_glyph::point(caret, baseline));
+            caret += scaled.h_advance(id);
+            if let Some(outline) = font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                outline.draw(|x, y, coverage| {
+                    let px = bounds.min.x as i32 + x as i32;
+                    let py = bounds.min.y as i32 + y as i32;
+                    self.blend(px, py, color, coverage);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Running a draw from the command line, without a window:
+//!
+//! ```text
+//! pinball_gacha --roster names.csv [--map map.json] [--seed N]
+//!               [--condition first|last|standing] [--clones best|worst]
+//!               [--format text|csv|json|markdown] [--png certificate.png]
+//!               [--commitment HASH]
+//! ```
+//!
+//! With the seed, roster and map of a published draw this re-runs it; the
+//! draw's commitment goes to stderr, and `--commitment` checks it. Without
+//! `--map` the draw runs on a random map, saved as `map-<seed>.json` so the
+//! commitment can be checked later.
+//!
+//! `pinball_gacha --verify-log audit_log.json [--head HASH]` checks an
+//! exported audit log's chain, and that it still ends at `HASH`.
+
+use crate::audit::{AuditLog, Event};
+use crate::export::{Format, Report};
+use crate::game::{CloneRank, GameState, WinningCondition};
+use crate::proof::Inputs;
+use crate::roster::Roster;
+
+const USAGE: &str = "Usage: pinball_gacha --roster FILE [--map FILE] [--seed N] \
+[--condition first|last|standing] [--clones best|worst] [--format text|csv|json|markdown] \
+[--png FILE] [--commitment HASH]
+       pinball_gacha --verify-log FILE [--head HASH]";
+
+struct Options {
+    roster: String,
+    map: Option<String>,
+    seed: Option<u64>,
+    condition: WinningCondition,
+    clones: CloneRank,
+    format: Format,
+    png: Option<String>,
+    commitment: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        roster: String::new(),
+        map: None,
+        seed: None,
+        condition: WinningCondition::First,
+        clones: CloneRank::Best,
+        format: Format::Text,
+        png: None,
+        commitment: None,
+    };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n{}", flag, USAGE))
+        };
+        match flag.as_str() {
+            "--roster" => options.roster = value()?,
+            "--map" => options.map = Some(value()?),
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("Seed \"{}\" is not a number", seed))?,
+                );
+            }
+            "--condition" => {
+                let key = value()?;
+                options.condition = parse_condition(&key)
+                    .ok_or_else(|| format!("Unknown condition \"{}\"\n{}", key, USAGE))?;
+            }
+            "--clones" => {
+                let key = value()?;
+                options.clones = parse_clones(&key)
+                    .ok_or_else(|| format!("Unknown clones \"{}\"\n{}", key, USAGE))?;
+            }
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    "markdown" | "md" => Format::Markdown,
+                    other => return Err(format!("Unknown format \"{}\"\n{}", other, USAGE)),
+                }
+            }
+            "--png" => options.png = Some(value()?),
+            "--commitment" => options.commitment = Some(value()?),
+            "--help" | "-h" => return Err(USAGE.to_owned()),
+            other => return Err(format!("Unknown argument \"{}\"\n{}", other, USAGE)),
+        }
+    }
+    if options.roster.is_empty() {
+        return Err(USAGE.to_owned());
+    }
+    Ok(options)
+}
+
+/// Run one draw to the end and print the results. Errors are messages for
+/// the user.
+pub fn run(args: &[String]) -> Result<(), String> {
+    if args.first().map(String::as_str) == Some("--verify-log") {
+        return verify_log(&args[1..]);
+    }
+    let options = parse_args(args)?;
+    let roster = Roster::parse(&crate::files::load_text(&options.roster)?);
+    if let Some(row) = roster.rows.iter().find(|r| !r.errors.is_empty()) {
+        return Err(format!(
+            "{} line {}: {}",
+            options.roster,
+            row.line,
+            row.errors.join("; ")
+        ));
+    }
+
+    let mut state = GameState::new();
+    state.headless = true;
+    if let Some(path) = &options.map {
+        let map = crate::game::map_file::MapFile::from_json(&crate::files::load_text(path)?)?;
+        state.load_map(map);
+    }
+    state.winning_condition = options.condition;
+    state.clone_rank = options.clones;
+    let seed = options.seed.unwrap_or_else(rand::random);
+    if options.map.is_none() {
+        let path = format!("map-{}.json", seed);
+        let saved = crate::files::save_text(&path, &state.map.to_json(), "application/json")?;
+        eprintln!("{} (random map)", saved);
+    }
+    let commitment = Inputs::new(
+        seed,
+        roster.entrants().map(|e| (e, e.tickets)),
+        &state.map,
+        &state,
+    )
+    .commitment();
+    eprintln!("Commitment: {}", commitment);
+    if let Some(claimed) = &options.commitment {
+        if !claimed.trim().eq_ignore_ascii_case(&commitment) {
+            return Err(format!(
+                "Commitment mismatch: the seed, roster, map or rules differ from {}",
+                claimed
+            ));
+        }
+    }
+    crate::proof::replay(&mut state, seed, &roster);
+    if !state.race_finished() {
+        eprintln!(
+            "Gave up after {}s with balls still on the board",
+            crate::game::MAX_RACE_TIME
+        );
+    }
+
+    let name = std::path::Path::new(&options.roster)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let report = Report::from_race(&state, &name);
+    print!("{}", report.render(options.format));
+    if let Some(path) = &options.png {
+        let bytes = crate::certificate::png(&state, &report)?;
+        eprintln!("{}", crate::files::save_bytes(path, &bytes, "image/png")?);
+    }
+    Ok(())
+}
+
+fn parse_condition(key: &str) -> Option<WinningCondition> {
+    match key {
+        "first" => Some(WinningCondition::First),
+        "last" => Some(WinningCondition::Last),
+        "standing" => Some(WinningCondition::LastStanding),
+        _ => None,
+    }
+}
+
+fn parse_clones(key: &str) -> Option<CloneRank> {
+    match key {
+        "best" => Some(CloneRank::Best),
+        "worst" => Some(CloneRank::Worst),
+        _ => None,
+    }
+}
+
+/// Check an exported audit log, optionally against a head hash noted earlier.
+fn verify_log(args: &[String]) -> Result<(), String> {
+    let (path, head) = match args {
+        [path] => (path, None),
+        [path, flag, head] if flag == "--head" => (path, Some(head.trim())),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let log = AuditLog::from_json(&crate::files::load_text(path)?)?;
+    if let Err(b) = log.verify() {
+        return Err(format!("Record #{} {}", b.index, b.reason));
+    }
+    if let Some(head) = head {
+        let i = log
+            .records
+            .iter()
+            .position(|r| r.hash.eq_ignore_ascii_case(head))
+            .ok_or_else(|| {
+                format!(
+                    "No record has hash {}: records were cut off or the log was replaced",
+                    head
+                )
+            })?;
+        if i + 1 < log.records.len() {
+            println!(
+                "{} record(s) were added after {}",
+                log.records.len() - i - 1,
+                head
+            );
+        }
+    }
+    let aborted = log
+        .records
+        .iter()
+        .filter(|r| r.event == Event::Aborted)
+        .count();
+    println!(
+        "Chain intact: {} records, {} aborted run(s), head {}",
+        log.records.len(),
+        aborted,
+        log.head()
+    );
+    Ok(())
+}
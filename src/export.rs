@@ -0,0 +1,189 @@
+//! A finished race written out for sharing: CSV, JSON, a Markdown table or
+//! plain text.
+
+use crate::files::csv_cell;
+use crate::game::GameState;
+use crate::history::{format_date, Placing};
+use serde::Serialize;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+    Text,
+}
+
+impl Format {
+    pub const FILES: [Format; 3] = [Format::Csv, Format::Json, Format::Markdown];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Csv => "CSV",
+            Format::Json => "JSON",
+            Format::Markdown => "Markdown",
+            Format::Text => "Text",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Markdown => "md",
+            Format::Text => "txt",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Format::Csv => "text/csv",
+            Format::Json => "application/json",
+            Format::Markdown => "text/markdown",
+            Format::Text => "text/plain",
+        }
+    }
+}
+
+/// Everything worth keeping about one race.
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub timestamp: u64, // Unix seconds
+    pub date: String,   // UTC
+    pub roster: String,
+    pub map: String,
+    pub map_id: String,
+    pub seed: u64,
+    pub condition: String,
+    pub winner: Option<String>,
+    pub ranking: Vec<Placing>, // One per person, best first
+    pub balls: Vec<Placing>,   // Every ball, in the order they left the board
+    pub stats: Stats,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Stats {
+    pub race_time: f64,
+    pub people: usize,
+    pub balls: usize,
+    pub finished: usize,
+    pub eliminated: usize,
+    pub survived: usize,
+}
+
+impl Report {
+    pub fn from_race(state: &GameState, roster: &str) -> Self {
+        let ranking: Vec<Placing> = state
+            .ranking()
+            .into_iter()
+            .map(|i| Placing::from_ball(state, i))
+            .collect();
+        let balls: Vec<Placing> = (0..state.finished_balls.len())
+            .map(|i| Placing::from_ball(state, i))
+            .collect();
+        let count = |outcome| balls.iter().filter(|b| b.outcome() == outcome).count();
+        let stats = Stats {
+            race_time: state.sim_time,
+            people: ranking.len(),
+            balls: balls.len(),
+            finished: count("finished"),
+            eliminated: count("eliminated"),
+            survived: count("survived"),
+        };
+        let timestamp = crate::history::now();
+        Self {
+            timestamp,
+            date: format_date(timestamp),
+            roster: roster.to_owned(),
+            map: state.map.name.clone(),
+            map_id: state.map.id(),
+            seed: state.seed,
+            condition: state.winning_condition.name().to_owned(),
+            winner: state
+                .winner()
+                .map(|i| state.owners[state.finished_balls[i].owner as usize].clone()),
+            ranking,
+            balls,
+            stats,
+        }
+    }
+
+    /// `results-<seed>.<extension>`, so exports of different races don't
+    /// overwrite each other.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!("results-{}.{}", self.seed, extension)
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Csv => self.to_csv(),
+            Format::Json => serde_json::to_string_pretty(self).expect("report serializes"),
+            Format::Markdown => self.to_markdown(),
+            Format::Text => self.to_text(),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("rank,name,ball,outcome,time\n");
+        for (i, p) in self.ranking.iter().enumerate() {
+            let cells = [
+                (i + 1).to_string(),
+                csv_cell(&p.name),
+                csv_cell(&p.ball),
+                p.outcome().to_owned(),
+                format!("{:.3}", p.time),
+            ];
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "**{}** — {} on *{}* ({} UTC, seed `{}`)\n\n",
+            self.winner.as_deref().unwrap_or("No winner"),
+            self.condition,
+            self.map,
+            self.date,
+            self.seed
+        );
+        md.push_str("| # | Name | Ball | Result | Time (s) |\n");
+        md.push_str("|---:|---|---|---|---:|\n");
+        for (i, p) in self.ranking.iter().enumerate() {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {:.2} |\n",
+                i + 1,
+                markdown_cell(&p.name),
+                markdown_cell(&p.ball),
+                p.outcome(),
+                p.time
+            ));
+        }
+        md
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "Winner: {}\n{} on {}, {} UTC, seed {}\n\n",
+            self.winner.as_deref().unwrap_or("-"),
+            self.condition,
+            self.map,
+            self.date,
+            self.seed
+        );
+        for (i, p) in self.ranking.iter().enumerate() {
+            let mark = match p.outcome() {
+                "eliminated" => " (out)",
+                "survived" => " (survived)",
+                _ => "",
+            };
+            text.push_str(&format!("{}. {}{}\n", i + 1, p.name, mark));
+        }
+        text
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
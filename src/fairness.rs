@@ -0,0 +1,227 @@
+//! Rotation rules for recurring draws: recent winners sit out or lose
+//! tickets, and people who haven't won in a while gain some. Rules belong to
+//! a roster and look only at that roster's past draws.
+
+use crate::history::History;
+use crate::roster::Entry;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fairness {
+    pub exclude_recent: u32, // Sit out after winning one of the last N draws (0 = off)
+    pub win_penalty: u32,    // Tickets off per win in the last `penalty_draws` draws
+    pub penalty_draws: u32,
+    pub drought_draws: u32, // After this many draws in a row without a win...
+    pub drought_bonus: u32, // ...gain this many tickets per further draw (0 = off)
+    pub max_bonus: u32,
+}
+
+impl Default for Fairness {
+    fn default() -> Self {
+        Self {
+            exclude_recent: 0,
+            win_penalty: 0,
+            penalty_draws: 5,
+            drought_draws: 3,
+            drought_bonus: 0,
+            max_bonus: 10,
+        }
+    }
+}
+
+/// What the rules made of one entrant.
+#[derive(Clone, Debug)]
+pub struct Adjusted {
+    pub name: String,
+    pub base: u32,
+    pub tickets: u32, // 0 = sitting this draw out
+    pub reason: String,
+}
+
+impl Fairness {
+    pub fn is_off(&self) -> bool {
+        self.exclude_recent == 0 && self.win_penalty == 0 && self.drought_bonus == 0
+    }
+
+    /// Effective tickets for each of `entrants`, given `roster`'s past draws
+    /// in `history`.
+    pub fn apply<'a>(
+        &self,
+        entrants: impl Iterator<Item = &'a Entry>,
+        history: &History,
+        roster: &str,
+    ) -> Vec<Adjusted> {
+        // Newest first
+        let draws: Vec<_> = history
+            .draws
+            .iter()
+            .rev()
+            .filter(|d| d.roster == roster)
+            .collect();
+        let won_within = |name: &str, n: u32| {
+            draws
+                .iter()
+                .take(n as usize)
+                .filter(|d| d.winner() == Some(name))
+                .count() as u32
+        };
+
+        entrants
+            .map(|entry| {
+                let name = entry.name.as_str();
+                let mut adjusted = Adjusted {
+                    name: entry.name.clone(),
+                    base: entry.tickets,
+                    tickets: entry.tickets,
+                    reason: String::new(),
+                };
+                if self.exclude_recent > 0 && won_within(name, self.exclude_recent) > 0 {
+                    adjusted.tickets = 0;
+                    adjusted.reason = match self.exclude_recent {
+                        1 => "won the last draw".to_owned(),
+                        n => format!("won in the last {} draws", n),
+                    };
+                    return adjusted;
+                }
+
+                let wins = won_within(name, self.penalty_draws);
+                if self.win_penalty > 0 && wins > 0 {
+                    let penalty = self.win_penalty * wins;
+                    adjusted.tickets = entry.tickets.saturating_sub(penalty).max(1);
+                    adjusted.reason = format!("-{} for {} recent win(s)", penalty, wins);
+                    return adjusted;
+                }
+
+                // Draws they took part in since their last win
+                let dry = draws
+                    .iter()
+                    .filter(|d| d.rank_of(name).is_some())
+                    .take_while(|d| d.winner() != Some(name))
+                    .count() as u32;
+                if self.drought_bonus > 0 && dry > self.drought_draws {
+                    let bonus =
+                        (self.drought_bonus * (dry - self.drought_draws)).min(self.max_bonus);
+                    adjusted.tickets = entry.tickets + bonus;
+                    adjusted.reason = format!("+{} after {} draws without a win", bonus, dry);
+                }
+                adjusted
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{Draw, Placing};
+
+    /// A history of `roster`'s draws, oldest first, each placed as listed.
+    fn history(roster: &str, draws: &[&[&str]]) -> History {
+        let draw = |order: &&[&str]| Draw {
+            timestamp: 0,
+            roster: roster.to_owned(),
+            map: String::new(),
+            seed: 0,
+            condition: String::new(),
+            order: order
+                .iter()
+                .map(|name| Placing {
+                    name: name.to_string(),
+                    ball: name.to_string(),
+                    eliminated: false,
+                    survived: false,
+                    time: 0.0,
+                })
+                .collect(),
+            entrants: Vec::new(),
+        };
+        History {
+            draws: draws.iter().map(draw).collect(),
+        }
+    }
+
+    fn tickets(fairness: &Fairness, history: &History, entrants: &[Entry]) -> Vec<u32> {
+        fairness
+            .apply(entrants.iter(), history, "Team")
+            .iter()
+            .map(|a| a.tickets)
+            .collect()
+    }
+
+    fn entries(tickets: &[(&str, u32)]) -> Vec<Entry> {
+        tickets
+            .iter()
+            .map(|&(name, tickets)| Entry {
+                tickets,
+                ..Entry::new(name)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn off_leaves_tickets_alone() {
+        let history = history("Team", &[&["Alice", "Bob"], &["Alice", "Bob"]]);
+        let entrants = entries(&[("Alice", 3), ("Bob", 2)]);
+        assert!(Fairness::default().is_off());
+        assert_eq!(tickets(&Fairness::default(), &history, &entrants), [3, 2]);
+    }
+
+    #[test]
+    fn recent_winner_sits_out() {
+        let history = history("Team", &[&["Bob", "Alice"], &["Alice", "Bob"]]);
+        let entrants = entries(&[("Alice", 3), ("Bob", 2)]);
+        let fairness = Fairness {
+            exclude_recent: 1,
+            ..Fairness::default()
+        };
+        assert_eq!(tickets(&fairness, &history, &entrants), [0, 2]);
+        let fairness = Fairness {
+            exclude_recent: 2,
+            ..Fairness::default()
+        };
+        assert_eq!(tickets(&fairness, &history, &entrants), [0, 0]);
+    }
+
+    #[test]
+    fn penalty_per_win_keeps_one_ticket() {
+        let history = history("Team", &[&["Alice"], &["Alice"], &["Bob"]]);
+        let entrants = entries(&[("Alice", 5), ("Bob", 2), ("Cara", 4)]);
+        let fairness = Fairness {
+            win_penalty: 2,
+            penalty_draws: 3,
+            ..Fairness::default()
+        };
+        assert_eq!(tickets(&fairness, &history, &entrants), [1, 1, 4]);
+    }
+
+    #[test]
+    fn drought_bonus_grows_to_the_cap() {
+        let dry: Vec<&[&str]> = vec![&["Alice", "Bob"]; 6];
+        let history = history("Team", &dry);
+        let entrants = entries(&[("Alice", 1), ("Bob", 1), ("Cara", 1)]);
+        let fairness = Fairness {
+            drought_draws: 3,
+            drought_bonus: 2,
+            max_bonus: 10,
+            ..Fairness::default()
+        };
+        // Bob: 6 dry draws, 3 past the threshold; Cara never raced
+        assert_eq!(tickets(&fairness, &history, &entrants), [1, 7, 1]);
+        let capped = Fairness {
+            max_bonus: 4,
+            ..fairness
+        };
+        assert_eq!(tickets(&capped, &history, &entrants), [1, 5, 1]);
+    }
+
+    #[test]
+    fn other_rosters_draws_are_ignored() {
+        let history = history("Other", &[&["Alice"]]);
+        let entrants = entries(&[("Alice", 3)]);
+        let fairness = Fairness {
+            exclude_recent: 5,
+            ..Fairness::default()
+        };
+        assert_eq!(tickets(&fairness, &history, &entrants), [3]);
+    }
+}
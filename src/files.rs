@@ -0,0 +1,105 @@
+//! Saving and loading user files: plain files on native, downloads in the browser.
+
+/// Save `bytes` under `file_name`. Native writes next to the working directory
+/// (or to the given path); the web build triggers a browser download.
+/// Returns a short status message for the UI.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_bytes(file_name: &str, bytes: &[u8], _mime: &str) -> Result<String, String> {
+    std::fs::write(file_name, bytes).map_err(|e| format!("Could not save {}: {}", file_name, e))?;
+    let path = std::fs::canonicalize(file_name)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| file_name.to_owned());
+    Ok(format!("Saved {}", path))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(file_name: &str, bytes: &[u8], mime: &str) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document")?;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|_| "Could not create download")?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(|_| "Could not create download")?;
+
+    let anchor = document
+        .create_element("a")
+        .map_err(|_| "Could not create download")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| "Could not create download")?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+
+    Ok(format!("Downloaded {}", file_name))
+}
+
+pub fn save_text(file_name: &str, contents: &str, mime: &str) -> Result<String, String> {
+    save_bytes(file_name, contents.as_bytes(), mime)
+}
+
+/// `name-YYYYMMDD-HHMMSS.extension` for now (UTC), for exports that would
+/// otherwise overwrite the last one.
+pub fn stamped_name(name: &str, extension: &str) -> String {
+    let now = crate::history::now();
+    let date = crate::history::format_date(now).replace(['-', ':'], "");
+    format!(
+        "{}-{}{:02}.{}",
+        name,
+        date.replace(' ', "-"),
+        now % 60,
+        extension
+    )
+}
+
+/// Read a text file from disk. Only available on native; the web build loads
+/// files through drag and drop instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_text(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))
+}
+
+/// Contents of a file dropped onto the window (native) or canvas (web).
+pub fn dropped_file_bytes(file: &egui::DroppedFile) -> Option<Vec<u8>> {
+    if let Some(bytes) = &file.bytes {
+        return Some(bytes.to_vec());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = &file.path {
+        return std::fs::read(path).ok();
+    }
+    None
+}
+
+/// Text contents of a dropped file.
+pub fn dropped_file_text(file: &egui::DroppedFile) -> Option<String> {
+    String::from_utf8(dropped_file_bytes(file)?).ok()
+}
+
+/// `text` as one CSV cell, quoted if it needs to be.
+pub fn csv_cell(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Display name of a dropped file.
+pub fn dropped_file_name(file: &egui::DroppedFile) -> String {
+    if !file.name.is_empty() {
+        return file.name.clone();
+    }
+    file.path
+        .as_ref()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,478 @@
+use crate::game::settings::PhysicsSettings;
+use serde::{Deserialize, Serialize};
+
+/// Everything placed on the board besides the fixed walls, funnel and goal
+/// (those always come from `maps::create_walls`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MapElement {
+    /// Round pin. Level 1-5 picks the elasticity; 0 is a plain editor pin.
+    Pin { x: f32, y: f32, level: u8 },
+    /// Large round bumper (funnel area).
+    Bumper {
+        x: f32,
+        y: f32,
+        radius: f32,
+        level: u8,
+    },
+    /// 45-degree square kicking balls off the side walls.
+    Deflector { x: f32, y: f32, level: u8 },
+    /// Straight wall segment.
+    Wall { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// Motor-driven windmill. `kind` 0-2 = slow/normal/fast colours.
+    Spinner {
+        x: f32,
+        y: f32,
+        length: f32,
+        speed: f32,
+        kind: u8,
+    },
+    /// Freely rotating plank.
+    Seesaw { x: f32, y: f32, width: f32 },
+    /// Teleporter pair. A ball entering the entry ring leaves the exit ring at
+    /// the same speed, its velocity turned by `rotation` degrees.
+    Portal {
+        entry_x: f32,
+        entry_y: f32,
+        exit_x: f32,
+        exit_y: f32,
+        radius: f32,
+        rotation: f32,
+    },
+    /// Force field acting on balls within `radius`; `strength` is the pull
+    /// (or push) at the centre, fading to nothing at the edge.
+    Field {
+        x: f32,
+        y: f32,
+        radius: f32,
+        strength: f32,
+        kind: FieldKind,
+    },
+    /// Area changing how balls move while inside. `points` is a convex polygon
+    /// (the editor draws rectangles); `angle` is the push direction in degrees
+    /// for wind and boost zones (0 = right, 90 = up).
+    Zone {
+        points: Vec<[f32; 2]>,
+        kind: ZoneKind,
+        strength: f32,
+        angle: f32,
+    },
+    /// Kinematic plank following `path`, one full cycle every `period`
+    /// seconds of race time. `angle` tilts the plank (degrees).
+    Platform {
+        width: f32,
+        angle: f32,
+        path: PlatformPath,
+        period: f32,
+        easing: Easing,
+    },
+    /// Wall segment that slides out of the way on a schedule: after `offset`
+    /// seconds it repeats `closed` seconds shut, `open` seconds open.
+    Gate {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        closed: f32,
+        open: f32,
+        offset: f32,
+    },
+    /// Block that cracks with every ball impact and breaks after `hits`.
+    /// Comes back for the next race.
+    Block {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        hits: u32,
+    },
+    /// Open line that turns solid once the first ball has gone through.
+    OneWayGate { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// Bank of `count` targets in a row along `angle` (degrees). A target
+    /// sinks when hit; once all are down the whole bank pops back up.
+    DropTargets {
+        x: f32,
+        y: f32,
+        count: u32,
+        angle: f32,
+    },
+    /// Eliminates any ball that touches it.
+    Trap { x: f32, y: f32, trap: TrapKind },
+    /// Bumper that splits a ball in two. Each splitter splits a ball (and
+    /// its clones) only once.
+    Splitter { x: f32, y: f32, radius: f32 },
+    /// Rope of `links` segments hanging straight down from (x, y).
+    Rope {
+        x: f32,
+        y: f32,
+        length: f32,
+        links: u32,
+    },
+    /// Chain of `links` segments sagging between two anchors.
+    Chain {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        links: u32,
+    },
+    /// Hammer on an arm hinged at (x, y), let go `angle` degrees out from
+    /// hanging straight down. A non-zero `motor` (rad/s) drives it round in
+    /// full circles instead.
+    Pendulum {
+        x: f32,
+        y: f32,
+        length: f32,
+        radius: f32,
+        angle: f32,
+        motor: f32,
+    },
+    /// Pachinko tulip: a pocket that holds a ball for `hold` seconds, then
+    /// drops it out of the bottom. Its wings open wide for a while whenever a
+    /// ball hits the `trigger` object, and snap shut when a ball is caught.
+    Tulip {
+        x: f32,
+        y: f32,
+        hold: f32,
+        #[serde(default)]
+        trigger: Option<u32>,
+    },
+    /// Motor-driven wheel with `cups` cups on its rim that scoop balls up
+    /// and tip them out over the top. `speed` in rad/s (positive =
+    /// counter-clockwise).
+    Wheel {
+        x: f32,
+        y: f32,
+        radius: f32,
+        cups: u32,
+        speed: f32,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum TrapKind {
+    /// Round hole.
+    Pit { radius: f32 },
+    /// Beam through (x, y) at `angle` (degrees), on for `on` seconds then
+    /// off for `off` seconds.
+    Laser {
+        length: f32,
+        angle: f32,
+        on: f32,
+        off: f32,
+    },
+    /// Block slamming down `drop` units once every `period` seconds.
+    Crusher { width: f32, drop: f32, period: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum PlatformPath {
+    /// Back and forth between two points.
+    Slider { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// Around a circle (counter-clockwise).
+    Orbit { x: f32, y: f32, radius: f32 },
+    /// Through each point in turn, then back (or round again if `looped`).
+    Waypoints { points: Vec<[f32; 2]>, looped: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    /// Slow down into and out of each waypoint (or each half orbit).
+    EaseInOut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FieldKind {
+    Attractor,
+    Repulsor,
+    /// Pulls like an attractor, then holds a ball at its core for a while and
+    /// spits it out in a random direction.
+    BlackHole,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ZoneKind {
+    /// Constant push (`strength` = acceleration).
+    Wind,
+    /// Extra linear damping (`strength` is added to the ball's damping).
+    Mud,
+    /// No friction or damping.
+    Ice,
+    /// One-off kick on entry (`strength` = speed added).
+    Boost,
+}
+
+impl ZoneKind {
+    pub const ALL: [ZoneKind; 4] = [
+        ZoneKind::Wind,
+        ZoneKind::Mud,
+        ZoneKind::Ice,
+        ZoneKind::Boost,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ZoneKind::Wind => "Wind",
+            ZoneKind::Mud => "Mud",
+            ZoneKind::Ice => "Ice",
+            ZoneKind::Boost => "Boost",
+        }
+    }
+
+    /// Default strength for new zones of this kind.
+    pub fn default_strength(self) -> f32 {
+        match self {
+            ZoneKind::Wind => 150.0,
+            ZoneKind::Mud => 4.0,
+            ZoneKind::Ice => 0.0,
+            ZoneKind::Boost => 300.0,
+        }
+    }
+
+    /// Whether `angle` means anything for this kind.
+    pub fn has_direction(self) -> bool {
+        matches!(self, ZoneKind::Wind | ZoneKind::Boost)
+    }
+}
+
+impl FieldKind {
+    pub const ALL: [FieldKind; 3] = [
+        FieldKind::Attractor,
+        FieldKind::Repulsor,
+        FieldKind::BlackHole,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldKind::Attractor => "Magnet",
+            FieldKind::Repulsor => "Repulsor",
+            FieldKind::BlackHole => "Black Hole",
+        }
+    }
+
+    /// Default strength for new fields of this kind.
+    pub fn default_strength(self) -> f32 {
+        match self {
+            FieldKind::Attractor => 600.0,
+            FieldKind::Repulsor => 900.0,
+            FieldKind::BlackHole => 500.0,
+        }
+    }
+}
+
+impl MapElement {
+    /// Short display name of the element type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapElement::Pin { .. } => "Pin",
+            MapElement::Bumper { .. } => "Bumper",
+            MapElement::Deflector { .. } => "Deflector",
+            MapElement::Wall { .. } => "Wall",
+            MapElement::Spinner { .. } => "Spinner",
+            MapElement::Seesaw { .. } => "Seesaw",
+            MapElement::Portal { .. } => "Portal",
+            MapElement::Field { kind, .. } => kind.name(),
+            MapElement::Zone { kind, .. } => kind.name(),
+            MapElement::Platform { .. } => "Platform",
+            MapElement::Gate { .. } => "Gate",
+            MapElement::Block { .. } => "Block",
+            MapElement::OneWayGate { .. } => "One-Way Gate",
+            MapElement::DropTargets { .. } => "Drop Targets",
+            MapElement::Trap { trap, .. } => match trap {
+                TrapKind::Pit { .. } => "Pit",
+                TrapKind::Laser { .. } => "Laser",
+                TrapKind::Crusher { .. } => "Crusher",
+            },
+            MapElement::Splitter { .. } => "Splitter",
+            MapElement::Rope { .. } => "Rope",
+            MapElement::Chain { .. } => "Chain",
+            MapElement::Pendulum { .. } => "Pendulum",
+            MapElement::Wheel { .. } => "Wheel",
+            MapElement::Tulip { .. } => "Tulip",
+        }
+    }
+}
+
+/// Map logic: when `trigger` happens during a race, do `action`. Links run
+/// in map order after each physics step, off race time only, so a replay
+/// fires them at the same moments.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Link {
+    pub trigger: Trigger,
+    pub action: Action,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Trigger {
+    /// A ball enters sensor object `object` (zone, portal, pocket...).
+    Entered { object: u32 },
+    /// Every `count`th ball hit on `object`.
+    Hits { object: u32, count: u32 },
+    /// The `count`th ball through the goal (once per race).
+    Finished { count: u32 },
+    /// `seconds` of race time have passed (once per race).
+    Time { seconds: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Action {
+    /// Hold gate `object` open for `seconds`, whatever its schedule says.
+    OpenGate { object: u32, seconds: f32 },
+    /// Turn the motor of a spinner, wheel or driven pendulum the other way.
+    Reverse { object: u32 },
+    /// Switch a pin, bumper or deflector to elasticity `level` (1-5).
+    SetLevel { object: u32, level: u8 },
+    /// Drop an extra obstacle onto the board. It takes no part in links and
+    /// goes again on reset.
+    Spawn { element: Box<MapElement> },
+    /// Change gravity (map physics gravity again on reset).
+    Gravity { gravity: f32 },
+}
+
+impl Trigger {
+    pub const NAMES: [&'static str; 4] = ["Ball Enters", "Hits", "Kth Finisher", "Time"];
+
+    /// Index into `NAMES`.
+    pub fn index(&self) -> usize {
+        match self {
+            Trigger::Entered { .. } => 0,
+            Trigger::Hits { .. } => 1,
+            Trigger::Finished { .. } => 2,
+            Trigger::Time { .. } => 3,
+        }
+    }
+
+    /// The object this trigger watches, if any.
+    pub fn object(&self) -> Option<u32> {
+        match *self {
+            Trigger::Entered { object } | Trigger::Hits { object, .. } => Some(object),
+            Trigger::Finished { .. } | Trigger::Time { .. } => None,
+        }
+    }
+}
+
+impl Action {
+    pub const NAMES: [&'static str; 5] = [
+        "Open Gate",
+        "Reverse Motor",
+        "Set Pin Level",
+        "Spawn",
+        "Gravity",
+    ];
+
+    /// Index into `NAMES`.
+    pub fn index(&self) -> usize {
+        match self {
+            Action::OpenGate { .. } => 0,
+            Action::Reverse { .. } => 1,
+            Action::SetLevel { .. } => 2,
+            Action::Spawn { .. } => 3,
+            Action::Gravity { .. } => 4,
+        }
+    }
+
+    /// The object this action changes, if any.
+    pub fn object(&self) -> Option<u32> {
+        match *self {
+            Action::OpenGate { object, .. }
+            | Action::Reverse { object }
+            | Action::SetLevel { object, .. } => Some(object),
+            Action::Spawn { .. } | Action::Gravity { .. } => None,
+        }
+    }
+
+    /// The usual action on `element`, for links drawn onto it in the editor.
+    pub fn default_for(object: u32, element: &MapElement) -> Option<Action> {
+        match element {
+            MapElement::Gate { .. } => Some(Action::OpenGate {
+                object,
+                seconds: 3.0,
+            }),
+            MapElement::Spinner { .. } | MapElement::Wheel { .. } => {
+                Some(Action::Reverse { object })
+            }
+            MapElement::Pendulum { motor, .. } if *motor != 0.0 => Some(Action::Reverse { object }),
+            MapElement::Pin { .. } | MapElement::Bumper { .. } | MapElement::Deflector { .. } => {
+                Some(Action::SetLevel { object, level: 5 })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapObject {
+    pub id: u32,
+    #[serde(flatten)]
+    pub element: MapElement,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapFile {
+    pub name: String,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub physics: PhysicsSettings,
+    #[serde(default)]
+    pub objects: Vec<MapObject>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+impl MapFile {
+    pub fn new(name: &str, width: f32, height: f32, elements: Vec<MapElement>) -> Self {
+        let mut map = Self {
+            name: name.to_owned(),
+            width,
+            height,
+            physics: PhysicsSettings::default(),
+            objects: Vec::new(),
+            links: Vec::new(),
+        };
+        for element in elements {
+            map.add(element);
+        }
+        map
+    }
+
+    /// Add an element with a fresh id and return that id.
+    pub fn add(&mut self, element: MapElement) -> u32 {
+        let id = self.objects.iter().map(|o| o.id).max().map_or(1, |m| m + 1);
+        self.objects.push(MapObject { id, element });
+        id
+    }
+
+    /// Remove an object and any links to or from it.
+    pub fn remove(&mut self, id: u32) {
+        self.objects.retain(|o| o.id != id);
+        self.links
+            .retain(|l| l.trigger.object() != Some(id) && l.action.object() != Some(id));
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("map serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut map: Self =
+            serde_json::from_str(json).map_err(|e| format!("Invalid map file: {}", e))?;
+        for object in &mut map.objects {
+            if let MapElement::Block { hits, .. } = &mut object.element {
+                *hits = (*hits).max(1); // A block breaks on its first hit at the earliest
+            }
+        }
+        Ok(map)
+    }
+
+    /// SHA-256 of the saved file, in hex: the same board gets the same id
+    /// wherever it was loaded.
+    pub fn id(&self) -> String {
+        crate::proof::sha256_hex(&self.to_json())
+    }
+}
@@ -1,16 +1,99 @@
+use crate::game::map_file::{MapElement, TrapKind};
 use crate::game::physics::PhysicsEngine;
+use crate::game::settings::PhysicsSettings;
 use rand::Rng;
 use rapier2d::prelude::*;
 
-fn get_elasticity_props(level: u8) -> (f32, u128) {
-    match level {
-        1 => (0.8, 11), // Blue - Low
-        2 => (1.0, 12), // Green - Med
-        3 => (1.5, 13), // Yellow - High
-        4 => (2.0, 14), // Orange - Super
-        5 => (3.0, 15), // Red - Extreme
-        _ => (0.8, 11), // Default
-    }
+// Funnel / chute dimensions shared by `create_walls` and `race_path`.
+const CHUTE_HEIGHT: f32 = 40.0;
+const FUNNEL_HEIGHT: f32 = 140.0;
+
+// Sensor `user_data` of the two portal rings.
+pub const PORTAL_ENTRY: u128 = 30;
+pub const PORTAL_EXIT: u128 = 31;
+
+// Sensor `user_data` of a force field's core (the fields themselves act by
+// distance in GameState::apply_fields; the core makes them erasable).
+pub const FIELD_CORE: u128 = 40;
+pub const BLACK_HOLE_CORE_RADIUS: f32 = 10.0;
+pub const FIELD_EDITOR_RADIUS: f32 = 80.0;
+
+// Sensor `user_data` of zones (wind, mud, ice, boost).
+pub const ZONE: u128 = 50;
+
+// `user_data` of kinematic platforms and gates.
+pub const PLATFORM: u128 = 60;
+pub const GATE: u128 = 61;
+
+// `user_data` of breakable and one-shot obstacles.
+pub const BLOCK: u128 = 70;
+pub const ONE_WAY: u128 = 71;
+pub const DROP_TARGET: u128 = 72;
+
+// `user_data` of pits, lasers and crushers.
+pub const TRAP: u128 = 80;
+
+// `user_data` of multiball splitters.
+pub const SPLITTER: u128 = 90;
+
+// `user_data` of jointed obstacles: rope and chain links, pendulum arms and
+// hammers, wheel cups.
+pub const LINK: u128 = 100;
+pub const PENDULUM: u128 = 101;
+pub const WHEEL: u128 = 102;
+
+// `user_data` of tulip cups and wings, and of the pocket sensor inside.
+pub const TULIP: u128 = 110;
+pub const TULIP_POCKET: u128 = 111;
+
+// Tulip cup: half the inside width, and the height of its sides; wing
+// length and angles (degrees from the +x axis, for the right wing; the left
+// one is mirrored).
+const TULIP_HALF_WIDTH: f32 = 14.0;
+const TULIP_DEPTH: f32 = 12.0;
+const TULIP_WING: f32 = 18.0;
+const TULIP_WING_SHUT: f32 = 105.0;
+const TULIP_WING_OPEN: f32 = 30.0;
+
+// How far a chain sags in the middle, as a share of the anchor distance.
+const CHAIN_SAG: f32 = 0.2;
+
+// Wheel cup: half the inside width, and the height of its sides.
+const CUP_HALF_WIDTH: f32 = 12.0;
+const CUP_DEPTH: f32 = 14.0;
+
+// Size of one drop target and the gap between targets in a bank.
+const DROP_TARGET_HALF: [f32; 2] = [8.0, 4.0];
+const DROP_TARGET_SPACING: f32 = 24.0;
+
+// Portal ring size, and the pins kept clear around each procedural ring.
+pub const PORTAL_RADIUS: f32 = 14.0;
+const PORTAL_CLEARANCE: f32 = 22.0;
+
+/// Physics handles created for one map element, so it can be erased again.
+#[derive(Default)]
+pub struct ElementHandles {
+    pub bodies: Vec<RigidBodyHandle>,
+    pub colliders: Vec<ColliderHandle>,
+}
+
+/// Restitution and `user_data` of a pin, bumper or deflector at elasticity
+/// `level`.
+pub fn get_elasticity_props(settings: &PhysicsSettings, level: u8) -> (f32, u128) {
+    // 11 Blue (low) to 15 Red (extreme); out-of-range levels act as level 1,
+    // as `PhysicsSettings::elasticity` does
+    let user_data = match level {
+        1..=5 => 10 + level as u128,
+        _ => 11,
+    };
+    (settings.elasticity(level), user_data)
+}
+
+/// Elasticity level (1-5) of a pin/bumper `user_data`, if it is one.
+pub fn elasticity_level(user_data: u128) -> Option<u8> {
+    (11..=15)
+        .contains(&user_data)
+        .then(|| (user_data - 10) as u8)
 }
 
 fn get_windmill_props(type_idx: u8) -> (f32, u128) {
@@ -22,15 +105,328 @@ fn get_windmill_props(type_idx: u8) -> (f32, u128) {
     }
 }
 
-pub fn create_map(physics: &mut PhysicsEngine, width: f32, height: f32) {
-    // Walls
-    create_walls(physics, width, height);
+/// Random layout for a new map: wall deflectors, bottom obstacles, an
+/// occasional portal pair and pins.
+pub fn generate_elements(width: f32, height: f32) -> Vec<MapElement> {
+    let mut elements = Vec::new();
+
+    // Wall Bumpers (Deflectors)
+    generate_deflectors(&mut elements, width, height);
 
     // Bottom Area Obstacles (Seesaws & Bumpers)
-    create_bottom_obstacles(physics, width, height);
+    generate_bottom_obstacles(&mut elements, width, height);
+
+    // Maybe a portal pair (pins keep clear of it)
+    generate_portal(&mut elements, width, height);
 
     // Default Pins
-    create_pins(physics, width, height);
+    generate_pins(&mut elements, width, height);
+
+    elements
+}
+
+/// Create the physics objects for one map element.
+pub fn build_element(
+    physics: &mut PhysicsEngine,
+    settings: &PhysicsSettings,
+    element: &MapElement,
+) -> ElementHandles {
+    let mut handles = ElementHandles::default();
+    match *element {
+        MapElement::Pin { x, y, level } => {
+            let collider = if level == 0 {
+                // Plain editor pin
+                ColliderBuilder::ball(5.0)
+                    .translation(vector![x, y])
+                    .restitution(0.7)
+                    .build()
+            } else {
+                let (restitution, user_data) = get_elasticity_props(settings, level);
+                ColliderBuilder::ball(5.0)
+                    .translation(vector![x, y])
+                    .restitution(restitution)
+                    .friction(0.0)
+                    .user_data(user_data)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build()
+            };
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::Bumper {
+            x,
+            y,
+            radius,
+            level,
+        } => {
+            let (restitution, user_data) = get_elasticity_props(settings, level);
+            let collider = ColliderBuilder::ball(radius)
+                .translation(vector![x, y])
+                .restitution(restitution)
+                .user_data(user_data)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::Deflector { x, y, level } => {
+            let (restitution, user_data) = get_elasticity_props(settings, level);
+            let collider = ColliderBuilder::cuboid(10.0, 10.0) // Doubled size (was 5.0)
+                .translation(vector![x, y])
+                .rotation(0.785) // 45 degrees
+                .restitution(restitution) // Bouncy
+                .friction(0.0)
+                .user_data(user_data)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::Wall { x1, y1, x2, y2 } => {
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            let length = (dx * dx + dy * dy).sqrt();
+            let angle = dy.atan2(dx);
+
+            let collider = ColliderBuilder::cuboid(length / 2.0, 5.0)
+                .translation(vector![(x1 + x2) / 2.0, (y1 + y2) / 2.0])
+                .rotation(angle)
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::Spinner {
+            x,
+            y,
+            length,
+            speed,
+            kind,
+        } => {
+            let (_, user_data) = get_windmill_props(kind);
+            handles = create_spinner(physics, x, y, length, speed, user_data);
+        }
+        MapElement::Seesaw { x, y, width } => {
+            handles = create_seesaw(physics, x, y, width);
+        }
+        MapElement::Portal {
+            entry_x,
+            entry_y,
+            exit_x,
+            exit_y,
+            radius,
+            ..
+        } => {
+            // Two sensors; the teleport itself happens in GameState::handle_collisions
+            for (x, y, user_data) in [
+                (entry_x, entry_y, PORTAL_ENTRY),
+                (exit_x, exit_y, PORTAL_EXIT),
+            ] {
+                let collider = ColliderBuilder::ball(radius)
+                    .translation(vector![x, y])
+                    .sensor(true)
+                    .user_data(user_data)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build();
+                handles
+                    .colliders
+                    .push(physics.collider_set.insert(collider));
+            }
+        }
+        MapElement::Zone { ref points, .. } => {
+            let points: Vec<Point<f32>> = points.iter().map(|p| point![p[0], p[1]]).collect();
+            if let Some(builder) = ColliderBuilder::convex_hull(&points) {
+                let collider = builder
+                    .sensor(true)
+                    .user_data(ZONE)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build();
+                handles
+                    .colliders
+                    .push(physics.collider_set.insert(collider));
+            }
+        }
+        MapElement::Platform { width, .. } => {
+            handles = create_kinematic(physics, width / 2.0, 4.0, PLATFORM);
+        }
+        MapElement::Gate { x1, y1, x2, y2, .. } => {
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            handles = create_kinematic(physics, length / 2.0, 5.0, GATE);
+        }
+        MapElement::Block {
+            x,
+            y,
+            width,
+            height,
+            ..
+        } => {
+            let collider = ColliderBuilder::cuboid(width / 2.0, height / 2.0)
+                .translation(vector![x, y])
+                .restitution(0.5)
+                .user_data(BLOCK)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::OneWayGate { x1, y1, x2, y2 } => {
+            // Starts as a sensor; GameState makes it solid behind the first ball
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            let collider = ColliderBuilder::cuboid((dx * dx + dy * dy).sqrt() / 2.0, 3.0)
+                .translation(vector![(x1 + x2) / 2.0, (y1 + y2) / 2.0])
+                .rotation(dy.atan2(dx))
+                .sensor(true)
+                .user_data(ONE_WAY)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::DropTargets { x, y, count, angle } => {
+            let angle = angle.to_radians();
+            let (sin, cos) = angle.sin_cos();
+            for i in 0..count {
+                let along = (i as f32 - (count as f32 - 1.0) / 2.0) * DROP_TARGET_SPACING;
+                let collider = ColliderBuilder::cuboid(DROP_TARGET_HALF[0], DROP_TARGET_HALF[1])
+                    .translation(vector![x + cos * along, y + sin * along])
+                    .rotation(angle)
+                    .restitution(0.8)
+                    .user_data(DROP_TARGET)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build();
+                handles
+                    .colliders
+                    .push(physics.collider_set.insert(collider));
+            }
+        }
+        MapElement::Trap { x, y, ref trap } => match *trap {
+            TrapKind::Pit { radius } => {
+                let collider = ColliderBuilder::ball(radius)
+                    .translation(vector![x, y])
+                    .sensor(true)
+                    .user_data(TRAP)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build();
+                handles
+                    .colliders
+                    .push(physics.collider_set.insert(collider));
+            }
+            TrapKind::Laser { length, angle, .. } => {
+                // Switched on and off by GameState::update_traps
+                let collider = ColliderBuilder::cuboid(length / 2.0, 2.0)
+                    .translation(vector![x, y])
+                    .rotation(angle.to_radians())
+                    .sensor(true)
+                    .user_data(TRAP)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                    .build();
+                handles
+                    .colliders
+                    .push(physics.collider_set.insert(collider));
+            }
+            TrapKind::Crusher { width, .. } => {
+                handles = create_kinematic(physics, width / 2.0, 10.0, TRAP);
+                for &collider in &handles.colliders {
+                    physics.collider_set[collider]
+                        .set_active_events(ActiveEvents::COLLISION_EVENTS);
+                }
+            }
+        },
+        MapElement::Rope {
+            x,
+            y,
+            length,
+            links,
+        } => {
+            let links = links.max(1);
+            let points: Vec<Vector<f32>> = (0..=links)
+                .map(|i| vector![x, y - length * i as f32 / links as f32])
+                .collect();
+            handles = create_links(physics, &points, 2.0, false);
+        }
+        MapElement::Chain {
+            x1,
+            y1,
+            x2,
+            y2,
+            links,
+        } => {
+            let links = links.max(2);
+            let sag = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt() * CHAIN_SAG;
+            let points: Vec<Vector<f32>> = (0..=links)
+                .map(|i| {
+                    let u = i as f32 / links as f32;
+                    vector![
+                        x1 + (x2 - x1) * u,
+                        y1 + (y2 - y1) * u - 4.0 * sag * u * (1.0 - u)
+                    ]
+                })
+                .collect();
+            handles = create_links(physics, &points, 3.0, true);
+        }
+        MapElement::Pendulum {
+            x,
+            y,
+            length,
+            radius,
+            angle,
+            motor,
+        } => {
+            handles = create_pendulum(physics, x, y, length, radius, angle, motor);
+        }
+        MapElement::Wheel {
+            x,
+            y,
+            radius,
+            cups,
+            speed,
+        } => {
+            handles = create_wheel(physics, x, y, radius, cups, speed);
+        }
+        MapElement::Tulip { x, y, .. } => {
+            handles = create_tulip(physics, x, y);
+        }
+        MapElement::Splitter { x, y, radius } => {
+            let collider = ColliderBuilder::ball(radius)
+                .translation(vector![x, y])
+                .restitution(0.8)
+                .user_data(SPLITTER)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+        MapElement::Field { x, y, .. } => {
+            let collider = ColliderBuilder::ball(BLACK_HOLE_CORE_RADIUS)
+                .translation(vector![x, y])
+                .sensor(true)
+                .user_data(FIELD_CORE)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            handles
+                .colliders
+                .push(physics.collider_set.insert(collider));
+        }
+    }
+    handles
 }
 
 pub fn create_walls(physics: &mut PhysicsEngine, width: f32, height: f32) {
@@ -77,47 +473,6 @@ pub fn create_walls(physics: &mut PhysicsEngine, width: f32, height: f32) {
         .build();
     physics.collider_set.insert(collider);
 
-    // Wall Bumpers (Deflectors)
-    // Small rotated boxes along the walls to kick balls back in
-    // Wall Bumpers (Deflectors)
-    // Small rotated boxes along the walls to kick balls back in
-    let mut rng = rand::thread_rng();
-    for i in 2..6 {
-        let y = -250.0 + (i as f32) * 100.0;
-
-        // Random restitution: 3.0 or 5.0
-        // Random elasticity for wall bumpers (High to Extreme: 3-5)
-        let level = rng.gen_range(3..=5);
-        let (restitution, user_data) = get_elasticity_props(level);
-
-        // Left Bumper
-        let collider = ColliderBuilder::cuboid(10.0, 10.0) // Doubled size (was 5.0)
-            .translation(vector![-width / 2.0 + 8.0, y]) // Protruding slightly
-            .rotation(0.785) // 45 degrees
-            .restitution(restitution) // Bouncy
-            .friction(0.0)
-            .user_data(user_data)
-            .active_events(ActiveEvents::COLLISION_EVENTS)
-            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
-            .build();
-        physics.collider_set.insert(collider);
-
-        let level_right = rng.gen_range(3..=5);
-        let (restitution_right, user_data_right) = get_elasticity_props(level_right);
-
-        // Right Bumper
-        let collider = ColliderBuilder::cuboid(10.0, 10.0)
-            .translation(vector![width / 2.0 - 8.0, y])
-            .rotation(0.785)
-            .restitution(restitution_right)
-            .friction(0.0)
-            .user_data(user_data_right)
-            .active_events(ActiveEvents::COLLISION_EVENTS)
-            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
-            .build();
-        physics.collider_set.insert(collider);
-    }
-
     // Top Wall (Lid)
     // Center: height/2 + 10 (original). Half-extent 10. Inner edge = h/2.
     // New: Half-extent 50. Inner edge h/2. Center = h/2 + 50.
@@ -139,7 +494,7 @@ pub fn create_walls(physics: &mut PhysicsEngine, width: f32, height: f32) {
     // We want a clear funnel: \ / leading to a narrow chute | |
     // AND it must be perfectly connected to the side walls so nothing escapes.
 
-    let chute_height = 40.0; // Shortened from 80.0
+    let chute_height = CHUTE_HEIGHT; // Shortened from 80.0
     let exit_gap = 22.0; // Slightly narrower? Or keep same.
 
     // Y Position:
@@ -155,7 +510,7 @@ pub fn create_walls(physics: &mut PhysicsEngine, width: f32, height: f32) {
     // New chute_top_y = bottom_y + 40.
     // To keep funnel_top_y at bottom_y + 180 (same top start), we need funnel_height = 140.
     // This effectively extends the funnel DOWNWARDS into the space freed by the shorter chute.
-    let funnel_height = 140.0;
+    let funnel_height = FUNNEL_HEIGHT;
 
     let bottom_y = -height / 2.0 + 20.0;
     let chute_top_y = bottom_y + chute_height;
@@ -270,11 +625,92 @@ pub fn create_walls(physics: &mut PhysicsEngine, width: f32, height: f32) {
     physics.collider_set.insert(collider);
 }
 
-pub fn create_pins(physics: &mut PhysicsEngine, width: f32, height: f32) {
+fn generate_deflectors(elements: &mut Vec<MapElement>, width: f32, _height: f32) {
+    // Small rotated boxes along the walls to kick balls back in
+    let mut rng = rand::thread_rng();
+    for i in 2..6 {
+        let y = -250.0 + (i as f32) * 100.0;
+
+        // Random elasticity for wall bumpers (High to Extreme: 3-5)
+        // Left Bumper (protruding slightly)
+        elements.push(MapElement::Deflector {
+            x: -width / 2.0 + 8.0,
+            y,
+            level: rng.gen_range(3..=5),
+        });
+
+        // Right Bumper
+        elements.push(MapElement::Deflector {
+            x: width / 2.0 - 8.0,
+            y,
+            level: rng.gen_range(3..=5),
+        });
+    }
+}
+
+/// Height a ball finishes at, partway down the chute.
+pub fn finish_y(height: f32) -> f32 {
+    -height / 2.0 + 50.0
+}
+
+/// Centre line a ball follows from the spawn area down to the finish line.
+/// Live standings measure progress as the distance left along this path.
+pub fn race_path(height: f32) -> Vec<[f32; 2]> {
+    let bottom_y = -height / 2.0 + 20.0;
+    let chute_top_y = bottom_y + CHUTE_HEIGHT;
+    let funnel_top_y = chute_top_y + FUNNEL_HEIGHT;
+
+    vec![
+        [0.0, height / 2.0],
+        [0.0, funnel_top_y],
+        [0.0, chute_top_y],
+        [0.0, finish_y(height)],
+    ]
+}
+
+fn generate_portal(elements: &mut Vec<MapElement>, _width: f32, _height: f32) {
+    let mut rng = rand::thread_rng();
+    if !rng.gen_bool(0.5) {
+        return;
+    }
+
+    // Entry low in the pin field, exit near the top: a ball that was almost
+    // through gets sent back up.
+    let rotations = [0.0, 0.0, 45.0, -45.0, 180.0];
+    elements.push(MapElement::Portal {
+        entry_x: rng.gen_range(-120.0..120.0),
+        entry_y: rng.gen_range(-40.0..60.0),
+        exit_x: rng.gen_range(-150.0..150.0),
+        exit_y: rng.gen_range(200.0..280.0),
+        radius: PORTAL_RADIUS,
+        rotation: rotations[rng.gen_range(0..rotations.len())],
+    });
+}
+
+/// Whether a pin at (x, y) would sit inside or right next to a portal ring.
+fn near_portal(elements: &[MapElement], x: f32, y: f32) -> bool {
+    elements.iter().any(|element| match *element {
+        MapElement::Portal {
+            entry_x,
+            entry_y,
+            exit_x,
+            exit_y,
+            radius,
+            ..
+        } => [(entry_x, entry_y), (exit_x, exit_y)]
+            .iter()
+            .any(|&(px, py)| {
+                let (dx, dy) = (x - px, y - py);
+                (dx * dx + dy * dy).sqrt() < radius + PORTAL_CLEARANCE
+            }),
+        _ => false,
+    })
+}
+
+fn generate_pins(elements: &mut Vec<MapElement>, width: f32, height: f32) {
     // Simple grid of pins
     let rows = 8; // Adjusted for 1.2x spacing (was 7 for 1.5x)
     let cols = 12; // Adjusted for 1.2x spacing (was 10 for 1.5x)
-    let pin_radius = 5.0;
 
     // Safety Margin: Bumpers need ~20 space. Wall is at 250. Inner Bumper edge ~230.
     // Pin should be at max ~210.
@@ -301,6 +737,9 @@ pub fn create_pins(physics: &mut PhysicsEngine, width: f32, height: f32) {
             }
 
             let y = height / 2.0 - 100.0 - (r as f32 * spacing_y);
+            if near_portal(elements, x, y) {
+                continue;
+            }
 
             // Random chance for a spinner instead of a pin
             let mut rng = rand::thread_rng();
@@ -311,14 +750,20 @@ pub fn create_pins(physics: &mut PhysicsEngine, width: f32, height: f32) {
 
                 // Random Speed Type
                 let type_idx = rng.gen_range(0..3);
-                let (speed_mag, user_data) = get_windmill_props(type_idx);
+                let (speed_mag, _user_data) = get_windmill_props(type_idx);
 
                 let speed = if rng.gen_bool(0.5) {
                     speed_mag
                 } else {
                     -speed_mag
                 };
-                create_spinner(physics, x, y, spinner_len, speed, user_data);
+                elements.push(MapElement::Spinner {
+                    x,
+                    y,
+                    length: spinner_len,
+                    speed,
+                    kind: type_idx,
+                });
                 continue;
             }
 
@@ -344,18 +789,7 @@ pub fn create_pins(physics: &mut PhysicsEngine, width: f32, height: f32) {
                 5
             }; // 5% Level 5
 
-            let (restitution, user_data) = get_elasticity_props(level);
-
-            let collider = ColliderBuilder::ball(pin_radius)
-                .translation(vector![x, y])
-                .restitution(restitution)
-                .friction(0.0)
-                .user_data(user_data)
-                .active_events(ActiveEvents::COLLISION_EVENTS)
-                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
-                .build();
-
-            physics.collider_set.insert(collider);
+            elements.push(MapElement::Pin { x, y, level });
         }
     }
 }
@@ -367,7 +801,7 @@ pub fn create_spinner(
     length: f32,
     speed: f32,
     user_data: u128,
-) {
+) -> ElementHandles {
     // 1. Static Anchor (invisible or small)
     let anchor_rb = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
     let anchor_handle = physics.rigid_body_set.insert(anchor_rb);
@@ -388,9 +822,11 @@ pub fn create_spinner(
             super::GROUP_BALL,
         )) // Spinner hits ONLY balls
         .build();
-    physics
-        .collider_set
-        .insert_with_parent(collider, blade_handle, &mut physics.rigid_body_set);
+    let blade_collider = physics.collider_set.insert_with_parent(
+        collider,
+        blade_handle,
+        &mut physics.rigid_body_set,
+    );
 
     // Cross blade (Vertical if first is horizontal)
     let collider2 = ColliderBuilder::cuboid(5.0, length / 2.0)
@@ -403,9 +839,11 @@ pub fn create_spinner(
             super::GROUP_BALL,
         ))
         .build();
-    physics
-        .collider_set
-        .insert_with_parent(collider2, blade_handle, &mut physics.rigid_body_set);
+    let cross_collider = physics.collider_set.insert_with_parent(
+        collider2,
+        blade_handle,
+        &mut physics.rigid_body_set,
+    );
 
     // 3. Joint with Motor
     // In rapier, we can use specific joint builders
@@ -417,9 +855,42 @@ pub fn create_spinner(
     physics
         .impulse_joint_set
         .insert(anchor_handle, blade_handle, joint, true);
+
+    ElementHandles {
+        bodies: vec![anchor_handle, blade_handle],
+        colliders: vec![blade_collider, cross_collider],
+    }
 }
 
-pub fn create_seesaw(physics: &mut PhysicsEngine, x: f32, y: f32, width: f32) {
+/// Kinematic box for platforms and gates. It is placed and moved by
+/// `GameState` from `motion::kinematic_pose`.
+fn create_kinematic(
+    physics: &mut PhysicsEngine,
+    half_width: f32,
+    half_height: f32,
+    user_data: u128,
+) -> ElementHandles {
+    let body = RigidBodyBuilder::kinematic_position_based().build();
+    let body_handle = physics.rigid_body_set.insert(body);
+
+    let collider = ColliderBuilder::cuboid(half_width, half_height)
+        .restitution(0.3)
+        .friction(0.3)
+        .user_data(user_data)
+        .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+        .build();
+    let collider_handle =
+        physics
+            .collider_set
+            .insert_with_parent(collider, body_handle, &mut physics.rigid_body_set);
+
+    ElementHandles {
+        bodies: vec![body_handle],
+        colliders: vec![collider_handle],
+    }
+}
+
+pub fn create_seesaw(physics: &mut PhysicsEngine, x: f32, y: f32, width: f32) -> ElementHandles {
     // 1. Static Pivot
     let pivot = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
     let pivot_handle = physics.rigid_body_set.insert(pivot);
@@ -436,9 +907,11 @@ pub fn create_seesaw(physics: &mut PhysicsEngine, x: f32, y: f32, width: f32) {
         .density(2.0)
         .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
         .build();
-    physics
-        .collider_set
-        .insert_with_parent(collider, plank_handle, &mut physics.rigid_body_set);
+    let plank_collider = physics.collider_set.insert_with_parent(
+        collider,
+        plank_handle,
+        &mut physics.rigid_body_set,
+    );
 
     // 3. Joint (Free rotation)
     let joint = RevoluteJointBuilder::new()
@@ -449,9 +922,288 @@ pub fn create_seesaw(physics: &mut PhysicsEngine, x: f32, y: f32, width: f32) {
     physics
         .impulse_joint_set
         .insert(pivot_handle, plank_handle, joint, true);
+
+    ElementHandles {
+        bodies: vec![pivot_handle, plank_handle],
+        colliders: vec![plank_collider],
+    }
+}
+
+/// Capsule links from `points[0]` through each point in turn, hinged to a
+/// fixed anchor and to each other. With `pinned_end` the last link is also
+/// hinged to a fixed anchor at the last point.
+fn create_links(
+    physics: &mut PhysicsEngine,
+    points: &[Vector<f32>],
+    thickness: f32,
+    pinned_end: bool,
+) -> ElementHandles {
+    let mut handles = ElementHandles::default();
+    let anchor = RigidBodyBuilder::fixed().translation(points[0]).build();
+    let mut prev = physics.rigid_body_set.insert(anchor);
+    let mut prev_end = point![0.0, 0.0];
+    handles.bodies.push(prev);
+
+    for pair in points.windows(2) {
+        let d = pair[1] - pair[0];
+        let half = d.norm() / 2.0;
+        let link = RigidBodyBuilder::dynamic()
+            .translation((pair[0] + pair[1]) / 2.0)
+            .rotation(d.y.atan2(d.x))
+            .linear_damping(0.5)
+            .angular_damping(0.5)
+            .build();
+        let link_handle = physics.rigid_body_set.insert(link);
+        let collider = ColliderBuilder::capsule_x(half, thickness)
+            .restitution(0.3)
+            .friction(0.3)
+            .density(2.0)
+            .user_data(LINK)
+            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+            .build();
+        handles
+            .colliders
+            .push(physics.collider_set.insert_with_parent(
+                collider,
+                link_handle,
+                &mut physics.rigid_body_set,
+            ));
+
+        let joint = RevoluteJointBuilder::new()
+            .local_anchor1(prev_end)
+            .local_anchor2(point![-half, 0.0]);
+        physics
+            .impulse_joint_set
+            .insert(prev, link_handle, joint, true);
+        handles.bodies.push(link_handle);
+        prev = link_handle;
+        prev_end = point![half, 0.0];
+    }
+
+    if pinned_end {
+        let end = RigidBodyBuilder::fixed()
+            .translation(points[points.len() - 1])
+            .build();
+        let end_handle = physics.rigid_body_set.insert(end);
+        let joint = RevoluteJointBuilder::new()
+            .local_anchor1(prev_end)
+            .local_anchor2(point![0.0, 0.0]);
+        physics
+            .impulse_joint_set
+            .insert(prev, end_handle, joint, true);
+        handles.bodies.push(end_handle);
+    }
+    handles
+}
+
+/// Arm and hammer hinged at (x, y), starting `angle` degrees out.
+fn create_pendulum(
+    physics: &mut PhysicsEngine,
+    x: f32,
+    y: f32,
+    length: f32,
+    radius: f32,
+    angle: f32,
+    motor: f32,
+) -> ElementHandles {
+    let pivot = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
+    let pivot_handle = physics.rigid_body_set.insert(pivot);
+
+    // Body origin at the hinge, so the rotation is the swing angle
+    let arm = RigidBodyBuilder::dynamic()
+        .translation(vector![x, y])
+        .rotation(angle.to_radians())
+        .build();
+    let arm_handle = physics.rigid_body_set.insert(arm);
+
+    let rod = ColliderBuilder::capsule_y(length / 2.0, 2.0)
+        .translation(vector![0.0, -length / 2.0])
+        .restitution(0.3)
+        .density(0.5)
+        .user_data(PENDULUM)
+        .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+        .build();
+    let head = ColliderBuilder::ball(radius)
+        .translation(vector![0.0, -length])
+        .restitution(0.6)
+        .density(5.0) // Heavy, so balls don't stop it
+        .user_data(PENDULUM)
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+        .build();
+    let rod_collider =
+        physics
+            .collider_set
+            .insert_with_parent(rod, arm_handle, &mut physics.rigid_body_set);
+    let head_collider =
+        physics
+            .collider_set
+            .insert_with_parent(head, arm_handle, &mut physics.rigid_body_set);
+
+    let mut joint = RevoluteJointBuilder::new()
+        .local_anchor1(point![0.0, 0.0])
+        .local_anchor2(point![0.0, 0.0]);
+    if motor != 0.0 {
+        joint = joint.motor_velocity(motor, 1.0e8);
+    }
+    physics
+        .impulse_joint_set
+        .insert(pivot_handle, arm_handle, joint, true);
+
+    ElementHandles {
+        bodies: vec![pivot_handle, arm_handle],
+        colliders: vec![rod_collider, head_collider],
+    }
+}
+
+/// Tulip cup (fixed), the pocket sensor inside it, and the two wings as
+/// kinematic bodies (left, right) hinged at the top of the cup sides.
+/// GameState swings the wings with `tulip_wing`.
+fn create_tulip(physics: &mut PhysicsEngine, x: f32, y: f32) -> ElementHandles {
+    let mut handles = ElementHandles::default();
+    let walls = [
+        (vector![x, y], vector![TULIP_HALF_WIDTH + 2.0, 2.0]),
+        (
+            vector![x - TULIP_HALF_WIDTH, y + TULIP_DEPTH / 2.0],
+            vector![2.0, TULIP_DEPTH / 2.0],
+        ),
+        (
+            vector![x + TULIP_HALF_WIDTH, y + TULIP_DEPTH / 2.0],
+            vector![2.0, TULIP_DEPTH / 2.0],
+        ),
+    ];
+    for (center, half) in walls {
+        let collider = ColliderBuilder::cuboid(half.x, half.y)
+            .translation(center)
+            .restitution(0.1)
+            .user_data(TULIP)
+            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+            .build();
+        handles
+            .colliders
+            .push(physics.collider_set.insert(collider));
+    }
+
+    let pocket = ColliderBuilder::cuboid(TULIP_HALF_WIDTH - 4.0, TULIP_DEPTH / 2.0 - 2.0)
+        .translation(vector![x, y + TULIP_DEPTH / 2.0])
+        .sensor(true)
+        .user_data(TULIP_POCKET)
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+        .build();
+    handles.colliders.push(physics.collider_set.insert(pocket));
+
+    for side in [-1.0, 1.0] {
+        let body = RigidBodyBuilder::kinematic_position_based()
+            .position(tulip_wing(x, y, side, 0.0))
+            .build();
+        let body_handle = physics.rigid_body_set.insert(body);
+        // Hinge at the body origin, wing sticking out along +x
+        let collider = ColliderBuilder::cuboid(TULIP_WING / 2.0, 2.0)
+            .translation(vector![TULIP_WING / 2.0, 0.0])
+            .restitution(0.3)
+            .user_data(TULIP)
+            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+            .build();
+        handles
+            .colliders
+            .push(physics.collider_set.insert_with_parent(
+                collider,
+                body_handle,
+                &mut physics.rigid_body_set,
+            ));
+        handles.bodies.push(body_handle);
+    }
+    handles
+}
+
+/// Pose of a tulip wing body: `side` -1 for the left wing, 1 for the
+/// right; `opening` from 0 (shut) to 1 (wide open).
+pub fn tulip_wing(x: f32, y: f32, side: f32, opening: f32) -> Isometry<f32> {
+    let angle = (TULIP_WING_SHUT + (TULIP_WING_OPEN - TULIP_WING_SHUT) * opening).to_radians();
+    let hinge = vector![x + side * TULIP_HALF_WIDTH, y + TULIP_DEPTH];
+    if side < 0.0 {
+        Isometry::new(hinge, std::f32::consts::PI - angle)
+    } else {
+        Isometry::new(hinge, angle)
+    }
+}
+
+/// Where a ball sits while a tulip at (x, y) holds it, and where it drops
+/// out when released.
+pub fn tulip_pocket(x: f32, y: f32) -> [Vector<f32>; 2] {
+    [
+        vector![x, y + TULIP_DEPTH / 2.0],
+        vector![x, y - TULIP_DEPTH],
+    ]
 }
 
-pub fn create_bottom_obstacles(physics: &mut PhysicsEngine, _width: f32, _height: f32) {
+/// Wheel turning at `speed` with `cups` U-shaped cups on its rim. Each cup
+/// faces up and forwards at the bottom of the turn and tips over at the top.
+fn create_wheel(
+    physics: &mut PhysicsEngine,
+    x: f32,
+    y: f32,
+    radius: f32,
+    cups: u32,
+    speed: f32,
+) -> ElementHandles {
+    let hub = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
+    let hub_handle = physics.rigid_body_set.insert(hub);
+    let wheel = RigidBodyBuilder::dynamic()
+        .translation(vector![x, y])
+        .build();
+    let wheel_handle = physics.rigid_body_set.insert(wheel);
+
+    // Cup frame: base along x, open towards +y
+    let parts = [
+        (vector![0.0, 0.0], vector![CUP_HALF_WIDTH + 2.0, 2.0]),
+        (
+            vector![-CUP_HALF_WIDTH, CUP_DEPTH / 2.0],
+            vector![2.0, CUP_DEPTH / 2.0],
+        ),
+        (
+            vector![CUP_HALF_WIDTH, CUP_DEPTH / 2.0],
+            vector![2.0, CUP_DEPTH / 2.0],
+        ),
+    ];
+    let tilt = if speed < 0.0 { 135.0f32 } else { 45.0 }.to_radians();
+    let mut colliders = Vec::new();
+    for i in 0..cups {
+        let at = i as f32 / cups as f32 * std::f32::consts::TAU;
+        let cup = Isometry::new(vector![at.cos(), at.sin()] * radius, at + tilt);
+        for (offset, half) in parts {
+            let collider = ColliderBuilder::cuboid(half.x, half.y)
+                .position(cup * Isometry::translation(offset.x, offset.y))
+                .restitution(0.1)
+                .friction(0.5)
+                .density(2.0)
+                .user_data(WHEEL)
+                .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
+                .build();
+            colliders.push(physics.collider_set.insert_with_parent(
+                collider,
+                wheel_handle,
+                &mut physics.rigid_body_set,
+            ));
+        }
+    }
+
+    let joint = RevoluteJointBuilder::new()
+        .local_anchor1(point![0.0, 0.0])
+        .local_anchor2(point![0.0, 0.0])
+        .motor_velocity(speed, 1.0e8);
+    physics
+        .impulse_joint_set
+        .insert(hub_handle, wheel_handle, joint, true);
+
+    ElementHandles {
+        bodies: vec![hub_handle, wheel_handle],
+        colliders,
+    }
+}
+
+fn generate_bottom_obstacles(elements: &mut Vec<MapElement>, _width: f32, _height: f32) {
     // Coordinate reference:
     // Funnel Top is roughly where pin grid ends.
     // Grid y: height / 2.0 - 100.0 - (10 * spacing) ~ 200 - 100 - (10*40) = -300 ?
@@ -467,16 +1219,19 @@ pub fn create_bottom_obstacles(physics: &mut PhysicsEngine, _width: f32, _height
 
     // Seesaws
     // New Upper Seesaws ( Cyan lines in user request)
-    create_seesaw(physics, -150.0, -100.0, 70.0);
-    create_seesaw(physics, 150.0, -100.0, 70.0);
-
-    // Moved up to avoid blocking goal
-    // Two top
-    create_seesaw(physics, -80.0, -160.0, 70.0);
-    create_seesaw(physics, 80.0, -160.0, 70.0);
-
-    // One bottom center
-    create_seesaw(physics, 0.0, -220.0, 80.0);
+    let seesaws = [
+        (-150.0, -100.0, 70.0),
+        (150.0, -100.0, 70.0),
+        // Moved up to avoid blocking goal
+        // Two top
+        (-80.0, -160.0, 70.0),
+        (80.0, -160.0, 70.0),
+        // One bottom center
+        (0.0, -220.0, 80.0),
+    ];
+    for (x, y, width) in seesaws {
+        elements.push(MapElement::Seesaw { x, y, width });
+    }
 
     // Funnel Bumpers (Elastic Pins on Funnel Walls)
     // Increased to 2 per side (4 total) and doubled size (radius ~20.0)
@@ -496,16 +1251,11 @@ pub fn create_bottom_obstacles(physics: &mut PhysicsEngine, _width: f32, _height
     let mut rng = rand::thread_rng();
 
     for (x, y) in bumper_configs {
-        let level = rng.gen_range(3..=5);
-        let (restitution, user_data) = get_elasticity_props(level);
-
-        let collider = ColliderBuilder::ball(13.0) // size doubled from 10.4
-            .translation(vector![x, y])
-            .restitution(restitution)
-            .user_data(user_data)
-            .active_events(ActiveEvents::COLLISION_EVENTS)
-            .collision_groups(InteractionGroups::new(super::GROUP_MAP, super::GROUP_BALL))
-            .build();
-        physics.collider_set.insert(collider);
+        elements.push(MapElement::Bumper {
+            x,
+            y,
+            radius: 13.0, // size doubled from 10.4
+            level: rng.gen_range(3..=5),
+        });
     }
 }
@@ -1,18 +1,61 @@
-use crate::game::physics::PhysicsEngine;
-use rand::Rng;
+use crate::game::map_file::{
+    Action, Easing, FieldKind, Link, MapElement, MapFile, PlatformPath, TrapKind, Trigger, ZoneKind,
+};
+use crate::game::physics::{PhysicsEngine, STEP_DT, SUBSTEPS_PER_FRAME};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rapier2d::prelude::*;
+use std::collections::{HashMap, HashSet};
 
+pub mod map_file;
 pub mod maps;
+pub mod motion;
+pub mod photo_finish;
 pub mod physics;
+pub mod settings;
+pub mod skin;
+pub mod standings;
 
 pub const GROUP_BALL: Group = Group::GROUP_1;
 pub const GROUP_MAP: Group = Group::GROUP_2;
 pub const GROUP_SPINNER: Group = Group::GROUP_3;
 
+// Fast-forward gives up after this much race time (a ball may be stuck).
+pub const MAX_RACE_TIME: f64 = 600.0;
+
+// Physics steps fast-forward runs per frame (4 s of race), so the window
+// keeps drawing and shows progress.
+const FAST_FORWARD_STEPS: u32 = 960;
+
+// A ball that just came out of a portal can't be teleported again for this
+// long (race time), so overlapping or facing rings don't ping-pong it.
+const PORTAL_COOLDOWN: f64 = 0.5;
+
+// Black holes hold a captured ball this long (race time), then throw it out
+// at this speed and leave it alone for a moment so it can escape the pull.
+pub const BLACK_HOLE_HOLD: f64 = 3.0;
+const BLACK_HOLE_EJECT_SPEED: f32 = 450.0;
+const BLACK_HOLE_COOLDOWN: f64 = 1.0;
+
+// Pause before a fully dropped target bank pops back up (race time).
+const DROP_TARGET_RESET_DELAY: f64 = 1.0;
+
+// Tulip wings stay open this long after their trigger is hit (race time),
+// and let a caught ball drop out this fast.
+const TULIP_OPEN_TIME: f64 = 2.0;
+const TULIP_DROP_SPEED: f32 = 60.0;
+
+// A split ball and its clone fly off this far (degrees) either side of the
+// original heading.
+const SPLIT_SPREAD: f32 = 20.0;
+
 pub struct Ball {
     pub name: String,
+    pub owner: u32, // Index into `GameState::owners`
     pub handle: RigidBodyHandle,
-    pub color: [u8; 3], // RGB
+    pub color: [u8; 3],   // RGB, shared by all of an owner's balls
+    pub skin: skin::Skin, // Drawn over the colour
+    pub splits: Vec<u32>, // Splitters that already split this ball (or its original)
 }
 
 #[derive(Clone, Copy)]
@@ -29,28 +72,151 @@ pub struct Particle {
 pub enum EditorTool {
     Pin,
     Wall,
+    Portal,
+    Field,
+    Zone,
+    Platform,
+    Gate,
+    Block,
+    OneWayGate,
+    DropTargets,
+    Trap,
+    Splitter,
+    Joint,
+    Tulip,
+    Link,
     Eraser,
 }
 
+/// Which jointed obstacle the Joint tool places.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum JointTool {
+    Rope,     // Click the anchor
+    Chain,    // Drag from one anchor to the other
+    Pendulum, // Click the hinge
+    Wheel,    // Click the hub
+}
+
+/// Which trap the Trap tool places.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TrapTool {
+    Pit,     // Click
+    Laser,   // Drag from one end of the beam to the other
+    Crusher, // Click
+}
+
+/// How the Platform tool lays out the path.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlatformTool {
+    Slider,    // Drag from one end to the other
+    Orbit,     // Drag from the centre out to the radius
+    Waypoints, // Click each point, then finish
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum WinningCondition {
     First,
     Last,
+    LastStanding, // Any way off the board is out; the last ball left wins
+}
+
+impl WinningCondition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WinningCondition::First => "First to Arrive",
+            WinningCondition::Last => "Last to Arrive",
+            WinningCondition::LastStanding => "Last Ball Standing",
+        }
+    }
+}
+
+/// Which of an owner's balls places them when a splitter gave them several.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CloneRank {
+    Best,
+    Worst,
+}
+
+/// How a ball left the board.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    Finished,   // Through the goal
+    Eliminated, // Caught by a trap
+    Survived,   // Last ball standing
+}
+
+/// A ball held in a black hole's core or a tulip pocket (its body is
+/// disabled meanwhile).
+pub struct Capture {
+    pub object: u32, // Map object id of the black hole or tulip
+    pub release_at: f64,
 }
 
 pub struct FinishedBall {
     pub name: String,
+    pub owner: u32,
     pub color: [u8; 3],
     pub finished_at: f64,
+    // Exact crossing of the finish line, in race time (interpolated within the step)
+    pub crossed_at: f64,
+    pub cross_x: f32,
+    pub cross_vel: [f32; 2],
+    pub outcome: Outcome,
 }
 
 pub struct GameState {
     pub physics: PhysicsEngine,
+    pub map: MapFile,
+    element_handles: HashMap<u32, maps::ElementHandles>,
+    collider_owner: HashMap<ColliderHandle, u32>, // Collider -> map object id
     pub balls: Vec<Ball>,
     pub finished_balls: Vec<FinishedBall>,
     pub winning_condition: WinningCondition,
+    pub owners: Vec<String>, // Entrants; clones share their original's owner
+    pub clone_rank: CloneRank,
+
+    // Live Standings
+    pub race_path: Vec<[f32; 2]>,
+    pub standings: Vec<standings::LiveStanding>,
+
+    // Photo Finish
+    pub photo_finishes: Vec<photo_finish::PhotoFinish>,
+    pub photo_finish_gap: f64,
+    pub slowmo_enabled: bool,
+    pub slowmo: f32, // Current playback factor (1.0 = normal)
+
+    // Portals: ball -> race time it may teleport again
+    portal_cooldowns: HashMap<RigidBodyHandle, f64>,
+    // Black holes: captured balls, and balls just released (-> race time)
+    pub captured: HashMap<RigidBodyHandle, Capture>,
+    black_hole_cooldowns: HashMap<RigidBodyHandle, f64>,
+    // Tulips: balls held in pockets, and when each tulip's wings opened
+    // and (will) shut again
+    pub pocketed: HashMap<RigidBodyHandle, Capture>,
+    pub tulip_wings: HashMap<u32, (f64, f64)>,
+    // Map logic: ball hits per object, one-shot links already fired, gates
+    // held open by links, obstacles links spawned, and objects links changed
+    // (rebuilt on reset)
+    link_hits: HashMap<u32, u32>,
+    links_fired: HashSet<usize>,
+    forced_gates: HashMap<u32, (f64, f64)>,
+    spawned: Vec<maps::ElementHandles>,
+    changed_objects: HashSet<u32>,
+    // Zones each ball is currently inside (from sensor start/stop events)
+    zone_members: HashMap<RigidBodyHandle, Vec<u32>>,
+    // Breakables: hits taken per block, and when dropped target banks pop up
+    pub block_damage: HashMap<u32, u32>,
+    drop_bank_resets: HashMap<u32, f64>,
 
     pub is_running: bool,
+    pub paused: bool,
+    pub time_scale: f32, // Playback speed (0.1x - 4x), physics step stays fixed
+    pub headless: bool,  // Skip visual effects (fast-forward)
+    pub fast_forwarding: bool, // Running the rest of the race a chunk per frame
+    pub seed: u64,       // Seeds `rng` for the current race
+    rng: StdRng,         // Race randomness (drops, ejections); effects use thread_rng
+    pub sim_time: f64,   // Race time simulated so far (seconds)
+    step_accumulator: f32,
     pub edit_mode: bool,
     pub selected_tool: EditorTool,
     pub map_width: f32,
@@ -59,6 +225,18 @@ pub struct GameState {
     // Editor State
     pub editor_drag_start: Option<(f32, f32)>,
     pub editor_grid_snap: bool,
+    pub editor_portal_rotation: f32, // Exit turn (degrees) for new portals
+    pub editor_field_kind: FieldKind,
+    pub editor_zone_kind: ZoneKind,
+    pub editor_zone_angle: f32,
+    pub editor_platform_tool: PlatformTool,
+    pub editor_waypoints: Vec<[f32; 2]>,
+    pub editor_waypoints_looped: bool,
+    pub editor_block_hits: u32,
+    pub editor_drop_count: u32,
+    pub editor_trap_tool: TrapTool,
+    pub editor_joint_tool: JointTool,
+    pub editor_pendulum_motor: bool,
 
     // Visual Effects
     pub particles: Vec<Particle>,
@@ -67,28 +245,262 @@ pub struct GameState {
 
 impl GameState {
     pub fn new() -> Self {
-        let mut physics = PhysicsEngine::new();
         // Initialize default map
         // Coordinate system: Center is (0,0). Width 500 means -250 to 250. Height 800 means -400 to 400.
         let width = 500.0;
         let height = 800.0;
 
-        maps::create_map(&mut physics, width, height);
+        let map = MapFile::new(
+            "Random Map",
+            width,
+            height,
+            maps::generate_elements(width, height),
+        );
 
-        Self {
-            physics,
+        let mut state = Self {
+            physics: PhysicsEngine::new(),
+            map,
+            element_handles: HashMap::new(),
+            collider_owner: HashMap::new(),
             balls: Vec::new(),
             finished_balls: Vec::new(),
             winning_condition: WinningCondition::First, // Changed default to First
+            owners: Vec::new(),
+            clone_rank: CloneRank::Best,
+            race_path: maps::race_path(height),
+            standings: Vec::new(),
+            photo_finishes: Vec::new(),
+            photo_finish_gap: photo_finish::DEFAULT_GAP,
+            slowmo_enabled: true,
+            slowmo: 1.0,
+            portal_cooldowns: HashMap::new(),
+            captured: HashMap::new(),
+            black_hole_cooldowns: HashMap::new(),
+            pocketed: HashMap::new(),
+            tulip_wings: HashMap::new(),
+            link_hits: HashMap::new(),
+            links_fired: HashSet::new(),
+            forced_gates: HashMap::new(),
+            spawned: Vec::new(),
+            changed_objects: HashSet::new(),
+            zone_members: HashMap::new(),
+            block_damage: HashMap::new(),
+            drop_bank_resets: HashMap::new(),
             is_running: false,
+            paused: false,
+            time_scale: 1.0,
+            headless: false,
+            fast_forwarding: false,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
+            sim_time: 0.0,
+            step_accumulator: 0.0,
             edit_mode: false,
             selected_tool: EditorTool::Pin,
             map_width: width,
             map_height: height,
             editor_drag_start: None,
             editor_grid_snap: true,
+            editor_portal_rotation: 0.0,
+            editor_field_kind: FieldKind::Attractor,
+            editor_zone_kind: ZoneKind::Wind,
+            editor_zone_angle: 0.0,
+            editor_platform_tool: PlatformTool::Slider,
+            editor_waypoints: Vec::new(),
+            editor_waypoints_looped: false,
+            editor_block_hits: 3,
+            editor_drop_count: 3,
+            editor_trap_tool: TrapTool::Pit,
+            editor_joint_tool: JointTool::Rope,
+            editor_pendulum_motor: false,
             particles: Vec::new(),
             hit_times: std::collections::HashMap::new(),
+        };
+        state.build_world();
+        state
+    }
+
+    /// Rebuild the physics world from `self.map`: fixed walls plus every map object.
+    pub fn build_world(&mut self) {
+        self.physics = PhysicsEngine::new();
+        self.element_handles.clear();
+        self.collider_owner.clear();
+        self.hit_times.clear();
+        self.spawned.clear();
+        self.changed_objects.clear();
+
+        maps::create_walls(&mut self.physics, self.map_width, self.map_height);
+        for i in 0..self.map.objects.len() {
+            let id = self.map.objects[i].id;
+            let element = self.map.objects[i].element.clone();
+            self.build_object(id, &element);
+        }
+
+        self.race_path = maps::race_path(self.map_height);
+        self.apply_physics_settings();
+    }
+
+    fn build_object(&mut self, id: u32, element: &MapElement) {
+        let handles = maps::build_element(&mut self.physics, &self.map.physics, element);
+        for &collider in &handles.colliders {
+            self.collider_owner.insert(collider, id);
+        }
+        self.element_handles.insert(id, handles);
+        self.place_kinematic(id, element, self.sim_time);
+    }
+
+    /// Colliders built for map object `id` (empty if it has none).
+    pub fn object_colliders(&self, id: u32) -> &[ColliderHandle] {
+        self.element_handles
+            .get(&id)
+            .map_or(&[], |handles| &handles.colliders)
+    }
+
+    /// Put a platform or gate where it belongs at race time `t` (no motion).
+    fn place_kinematic(&mut self, id: u32, element: &MapElement, t: f64) {
+        let Some(pose) = motion::kinematic_pose(element, t) else {
+            return;
+        };
+        let Some(handles) = self.element_handles.get(&id) else {
+            return;
+        };
+        for &body in &handles.bodies {
+            if let Some(rb) = self.physics.rigid_body_set.get_mut(body) {
+                rb.set_position(pose, true);
+                rb.set_next_kinematic_position(pose);
+            }
+        }
+    }
+
+    /// Move platforms and gates to where they will be at race time `t`; the
+    /// physics step derives their velocity from that, so contacts are right.
+    fn drive_kinematics(&mut self, t: f64) {
+        let poses: Vec<(u32, Isometry<f32>)> = self
+            .map
+            .objects
+            .iter()
+            .filter_map(|object| {
+                Some((
+                    object.id,
+                    self.kinematic_pose(object.id, &object.element, t)?,
+                ))
+            })
+            .collect();
+        for (id, pose) in poses {
+            let Some(handles) = self.element_handles.get(&id) else {
+                continue;
+            };
+            for &body in &handles.bodies {
+                if let Some(rb) = self.physics.rigid_body_set.get_mut(body) {
+                    rb.set_next_kinematic_position(pose);
+                }
+            }
+        }
+    }
+
+    /// `motion::kinematic_pose`, plus gates held open by links.
+    fn kinematic_pose(&self, id: u32, element: &MapElement, t: f64) -> Option<Isometry<f32>> {
+        if let MapElement::Gate {
+            x1,
+            y1,
+            x2,
+            y2,
+            closed,
+            open,
+            offset,
+        } = *element
+        {
+            if let Some(&(opened_at, closes_at)) = self.forced_gates.get(&id) {
+                let opened = motion::gate_opening(closed, open, offset, t)
+                    .max(motion::forced_gate_opening(opened_at, closes_at, t));
+                return Some(motion::gate_pose(x1, y1, x2, y2, opened));
+            }
+        }
+        motion::kinematic_pose(element, t)
+    }
+
+    /// Add an element to the map and the running world.
+    pub fn add_map_element(&mut self, element: MapElement) -> u32 {
+        let id = self.map.add(element.clone());
+        self.build_object(id, &element);
+        id
+    }
+
+    /// Remove a map object and everything it created in the world.
+    pub fn remove_map_object(&mut self, id: u32) {
+        self.map.remove(id);
+        for zones in self.zone_members.values_mut() {
+            zones.retain(|&zone| zone != id);
+        }
+        self.despawn_object(id);
+    }
+
+    /// Take a map object out of the world but keep it in the map (broken
+    /// blocks come back with `restore_obstacles`).
+    fn despawn_object(&mut self, id: u32) {
+        let Some(handles) = self.element_handles.remove(&id) else {
+            return;
+        };
+        self.collider_owner.retain(|_, owner| *owner != id);
+        self.remove_handles(handles);
+    }
+
+    fn remove_handles(&mut self, handles: maps::ElementHandles) {
+        for handle in handles.bodies {
+            // Also removes attached colliders and joints
+            self.physics.rigid_body_set.remove(
+                handle,
+                &mut self.physics.island_manager,
+                &mut self.physics.collider_set,
+                &mut self.physics.impulse_joint_set,
+                &mut self.physics.multibody_joint_set,
+                true,
+            );
+        }
+        for handle in handles.colliders {
+            self.physics.collider_set.remove(
+                handle,
+                &mut self.physics.island_manager,
+                &mut self.physics.rigid_body_set,
+                true,
+            );
+        }
+    }
+
+    /// Replace the current map (e.g. loaded from a file) and rebuild the world.
+    pub fn load_map(&mut self, map: MapFile) {
+        self.reset_game();
+        self.map_width = map.width;
+        self.map_height = map.height;
+        self.map = map;
+        self.build_world();
+    }
+
+    /// Push `self.map.physics` into the world: gravity, solver, pin elasticity
+    /// and the balls already on the board.
+    pub fn apply_physics_settings(&mut self) {
+        let settings = self.map.physics.clone();
+        self.physics.gravity = vector![0.0, settings.gravity];
+        self.physics.integration_parameters.num_solver_iterations =
+            std::num::NonZeroUsize::new(settings.solver_iterations.max(1)).unwrap();
+
+        for (_handle, collider) in self.physics.collider_set.iter_mut() {
+            if let Some(level) = maps::elasticity_level(collider.user_data) {
+                collider.set_restitution(settings.elasticity(level));
+            }
+        }
+
+        for ball in &self.balls {
+            if let Some(rb) = self.physics.rigid_body_set.get_mut(ball.handle) {
+                rb.set_linear_damping(settings.ball_linear_damping);
+                rb.enable_ccd(settings.ccd_enabled);
+                for &collider in rb.colliders() {
+                    if let Some(collider) = self.physics.collider_set.get_mut(collider) {
+                        collider.set_restitution(settings.ball_restitution);
+                        collider.set_shape(SharedShape::ball(settings.ball_radius));
+                    }
+                }
+            }
         }
     }
 
@@ -102,7 +514,7 @@ impl GameState {
     }
 
     pub fn editor_input_start(&mut self, x: f32, y: f32) {
-        if !self.edit_mode {
+        if !self.edit_mode || self.race_in_progress() {
             return;
         }
 
@@ -116,13 +528,100 @@ impl GameState {
                 // But for pin, maybe better to place on release to avoid duplicates if dragged?
                 // Or "painting" support.
                 // For now, let's treat Pin as "Place on click" (start).
-                let collider = ColliderBuilder::ball(5.0)
-                    .translation(vector![x, y])
-                    .restitution(0.7)
-                    .build();
-                self.physics.collider_set.insert(collider);
+                self.add_map_element(MapElement::Pin { x, y, level: 0 });
             }
-            EditorTool::Wall => {
+            EditorTool::Trap => match self.editor_trap_tool {
+                TrapTool::Pit => {
+                    self.add_map_element(MapElement::Trap {
+                        x,
+                        y,
+                        trap: TrapKind::Pit { radius: 16.0 },
+                    });
+                }
+                TrapTool::Laser => self.editor_drag_start = Some((x, y)),
+                TrapTool::Crusher => {
+                    self.add_map_element(MapElement::Trap {
+                        x,
+                        y,
+                        trap: TrapKind::Crusher {
+                            width: 60.0,
+                            drop: 80.0,
+                            period: 3.0,
+                        },
+                    });
+                }
+            },
+            EditorTool::Block => {
+                self.add_map_element(MapElement::Block {
+                    x,
+                    y,
+                    width: 40.0,
+                    height: 16.0,
+                    hits: self.editor_block_hits.max(1),
+                });
+            }
+            EditorTool::Joint => match self.editor_joint_tool {
+                JointTool::Rope => {
+                    self.add_map_element(MapElement::Rope {
+                        x,
+                        y,
+                        length: 120.0,
+                        links: 10,
+                    });
+                }
+                JointTool::Chain => self.editor_drag_start = Some((x, y)),
+                JointTool::Pendulum => {
+                    let motor = if self.editor_pendulum_motor { 3.0 } else { 0.0 };
+                    self.add_map_element(MapElement::Pendulum {
+                        x,
+                        y,
+                        length: 90.0,
+                        radius: 14.0,
+                        angle: 60.0,
+                        motor,
+                    });
+                }
+                JointTool::Wheel => {
+                    self.add_map_element(MapElement::Wheel {
+                        x,
+                        y,
+                        radius: 60.0,
+                        cups: 4,
+                        speed: 1.0,
+                    });
+                }
+            },
+            EditorTool::Tulip | EditorTool::Link => self.editor_drag_start = Some((x, y)),
+            EditorTool::Splitter => {
+                self.add_map_element(MapElement::Splitter { x, y, radius: 12.0 });
+            }
+            EditorTool::DropTargets => {
+                self.add_map_element(MapElement::DropTargets {
+                    x,
+                    y,
+                    count: self.editor_drop_count,
+                    angle: 0.0,
+                });
+            }
+            EditorTool::Field => {
+                let kind = self.editor_field_kind;
+                self.add_map_element(MapElement::Field {
+                    x,
+                    y,
+                    radius: maps::FIELD_EDITOR_RADIUS,
+                    strength: kind.default_strength(),
+                    kind,
+                });
+            }
+            EditorTool::Platform if self.editor_platform_tool == PlatformTool::Waypoints => {
+                self.editor_waypoints.push([x, y]);
+            }
+            EditorTool::Wall
+            | EditorTool::Portal
+            | EditorTool::Zone
+            | EditorTool::Platform
+            | EditorTool::Gate
+            | EditorTool::OneWayGate => {
                 // Start dragging
                 self.editor_drag_start = Some((x, y));
             }
@@ -134,7 +633,7 @@ impl GameState {
     }
 
     pub fn editor_input_end(&mut self, x: f32, y: f32) {
-        if !self.edit_mode {
+        if !self.edit_mode || self.race_in_progress() {
             return;
         }
 
@@ -142,9 +641,80 @@ impl GameState {
         let y = self.editor_snap(y);
 
         match self.selected_tool {
-            EditorTool::Pin => {
+            EditorTool::Pin
+            | EditorTool::Field
+            | EditorTool::Block
+            | EditorTool::DropTargets
+            | EditorTool::Splitter => {
                 // Already placed on start
             }
+            EditorTool::Tulip => {
+                // Click places a tulip; dragging from a tulip to another
+                // object makes that object its trigger
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    if (dx * dx + dy * dy).sqrt() <= 5.0 {
+                        self.add_map_element(MapElement::Tulip {
+                            x: start_x,
+                            y: start_y,
+                            hold: 1.5,
+                            trigger: None,
+                        });
+                    } else if let (Some(tulip), Some(target)) =
+                        (self.object_at(start_x, start_y), self.object_at(x, y))
+                    {
+                        self.set_tulip_trigger(tulip, target);
+                    }
+                }
+            }
+            EditorTool::Link => {
+                // Drag from the object that triggers to the one that acts
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    if let (Some(from), Some(to)) =
+                        (self.object_at(start_x, start_y), self.object_at(x, y))
+                    {
+                        self.editor_link(from, to);
+                    }
+                }
+            }
+            EditorTool::Joint => {
+                // Everything but chains was placed on start
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    let length = (dx * dx + dy * dy).sqrt();
+                    if length > 5.0 {
+                        self.add_map_element(MapElement::Chain {
+                            x1: start_x,
+                            y1: start_y,
+                            x2: x,
+                            y2: y,
+                            links: ((length / 15.0) as u32).max(2),
+                        });
+                    }
+                }
+            }
+            EditorTool::Trap => {
+                // Pits and crushers were placed on start; lasers are dragged
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    let length = (dx * dx + dy * dy).sqrt();
+                    if length > 5.0 {
+                        self.add_map_element(MapElement::Trap {
+                            x: (start_x + x) / 2.0,
+                            y: (start_y + y) / 2.0,
+                            trap: TrapKind::Laser {
+                                length,
+                                angle: dy.atan2(dx).to_degrees(),
+                                on: 2.0,
+                                off: 2.0,
+                            },
+                        });
+                    }
+                }
+            }
             EditorTool::Wall => {
                 if let Some((start_x, start_y)) = self.editor_drag_start {
                     // Create wall from start to current
@@ -153,83 +723,723 @@ impl GameState {
                     let length = (dx * dx + dy * dy).sqrt();
 
                     if length > 5.0 {
-                        // Center point
-                        let cx = (start_x + x) / 2.0;
-                        let cy = (start_y + y) / 2.0;
-                        let angle = dy.atan2(dx);
-
-                        let collider = ColliderBuilder::cuboid(length / 2.0, 5.0)
-                            .translation(vector![cx, cy])
-                            .rotation(angle)
-                            .build();
-                        self.physics.collider_set.insert(collider);
+                        self.add_map_element(MapElement::Wall {
+                            x1: start_x,
+                            y1: start_y,
+                            x2: x,
+                            y2: y,
+                        });
                     }
 
                     self.editor_drag_start = None;
                 }
             }
-            EditorTool::Eraser => {
-                // Optional: Erase on end too ("painting" eraser if dragged?)
-                // For now just click
+            EditorTool::Portal => {
+                // Drag from the entry to the exit
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    if (dx * dx + dy * dy).sqrt() > maps::PORTAL_RADIUS * 2.0 {
+                        self.add_map_element(MapElement::Portal {
+                            entry_x: start_x,
+                            entry_y: start_y,
+                            exit_x: x,
+                            exit_y: y,
+                            radius: maps::PORTAL_RADIUS,
+                            rotation: self.editor_portal_rotation,
+                        });
+                    }
+                }
+            }
+            EditorTool::Zone => {
+                // Drag a rectangle
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let (x1, x2) = (start_x.min(x), start_x.max(x));
+                    let (y1, y2) = (start_y.min(y), start_y.max(y));
+                    if x2 - x1 > 5.0 && y2 - y1 > 5.0 {
+                        let kind = self.editor_zone_kind;
+                        self.add_map_element(MapElement::Zone {
+                            points: vec![[x1, y1], [x2, y1], [x2, y2], [x1, y2]],
+                            kind,
+                            strength: kind.default_strength(),
+                            angle: self.editor_zone_angle,
+                        });
+                    }
+                }
+            }
+            EditorTool::Platform => {
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    let length = (dx * dx + dy * dy).sqrt();
+                    let path = match self.editor_platform_tool {
+                        PlatformTool::Slider => PlatformPath::Slider {
+                            x1: start_x,
+                            y1: start_y,
+                            x2: x,
+                            y2: y,
+                        },
+                        PlatformTool::Orbit => PlatformPath::Orbit {
+                            x: start_x,
+                            y: start_y,
+                            radius: length,
+                        },
+                        PlatformTool::Waypoints => return, // Placed point by point
+                    };
+                    if length > 5.0 {
+                        self.add_platform(path);
+                    }
+                }
+            }
+            EditorTool::OneWayGate => {
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    if (dx * dx + dy * dy).sqrt() > 5.0 {
+                        self.add_map_element(MapElement::OneWayGate {
+                            x1: start_x,
+                            y1: start_y,
+                            x2: x,
+                            y2: y,
+                        });
+                    }
+                }
+            }
+            EditorTool::Gate => {
+                if let Some((start_x, start_y)) = self.editor_drag_start.take() {
+                    let dx = x - start_x;
+                    let dy = y - start_y;
+                    if (dx * dx + dy * dy).sqrt() > 5.0 {
+                        self.add_map_element(MapElement::Gate {
+                            x1: start_x,
+                            y1: start_y,
+                            x2: x,
+                            y2: y,
+                            closed: 3.0,
+                            open: 2.0,
+                            offset: 0.0,
+                        });
+                    }
+                }
+            }
+            EditorTool::Eraser => {
+                // Optional: Erase on end too ("painting" eraser if dragged?)
+                // For now just click
+            }
+        }
+    }
+
+    /// Turn the clicked waypoints into a platform.
+    pub fn editor_finish_waypoints(&mut self) {
+        if self.race_in_progress() {
+            return;
+        }
+        let points = std::mem::take(&mut self.editor_waypoints);
+        if points.len() >= 2 {
+            self.add_platform(PlatformPath::Waypoints {
+                points,
+                looped: self.editor_waypoints_looped,
+            });
+        }
+    }
+
+    fn add_platform(&mut self, path: PlatformPath) {
+        self.add_map_element(MapElement::Platform {
+            width: 60.0,
+            angle: 0.0,
+            path,
+            period: 4.0,
+            easing: Easing::EaseInOut,
+        });
+    }
+
+    /// The map object with a collider at (x, y), if any.
+    fn object_at(&mut self, x: f32, y: f32) -> Option<u32> {
+        self.physics
+            .query_pipeline
+            .update(&self.physics.rigid_body_set, &self.physics.collider_set);
+        let mut found = None;
+        self.physics.query_pipeline.intersections_with_point(
+            &self.physics.rigid_body_set,
+            &self.physics.collider_set,
+            &point![x, y],
+            QueryFilter::default(),
+            |handle| {
+                found = self.collider_owner.get(&handle).copied();
+                found.is_none()
+            },
+        );
+        found
+    }
+
+    /// Link a ball touching `from` to the usual action on `to` (see
+    /// `Action::default_for`); the logic panel can change both after.
+    fn editor_link(&mut self, from: u32, to: u32) {
+        if from == to {
+            return;
+        }
+        let Some(action) = self
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == to)
+            .and_then(|o| Action::default_for(to, &o.element))
+        else {
+            return;
+        };
+        let sensor = self
+            .object_colliders(from)
+            .first()
+            .and_then(|&c| self.physics.collider_set.get(c))
+            .is_some_and(|c| c.is_sensor());
+        let trigger = if sensor {
+            Trigger::Entered { object: from }
+        } else {
+            Trigger::Hits {
+                object: from,
+                count: 1,
+            }
+        };
+        self.add_link(Link { trigger, action });
+    }
+
+    /// Wire `target` up as the trigger of tulip `tulip` (no-op if `tulip`
+    /// isn't a tulip, or is `target`).
+    fn set_tulip_trigger(&mut self, tulip: u32, target: u32) {
+        if tulip == target {
+            return;
+        }
+        if let Some(MapElement::Tulip { trigger, .. }) = self
+            .map
+            .objects
+            .iter_mut()
+            .find(|o| o.id == tulip)
+            .map(|o| &mut o.element)
+        {
+            *trigger = Some(target);
+        }
+    }
+
+    fn editor_erase(&mut self, x: f32, y: f32) {
+        let point = point![x, y];
+        self.physics
+            .query_pipeline
+            .update(&self.physics.rigid_body_set, &self.physics.collider_set);
+
+        let filter = QueryFilter::default();
+        let mut object_to_remove = None;
+
+        // Only map objects: the fixed walls aren't in the map file, so an
+        // erased wall would be back when the world is rebuilt at the start
+        let collider_owner = &self.collider_owner;
+        self.physics.query_pipeline.intersections_with_point(
+            &self.physics.rigid_body_set,
+            &self.physics.collider_set,
+            &point,
+            filter,
+            |handle| match collider_owner.get(&handle) {
+                Some(&id) => {
+                    object_to_remove = Some(id);
+                    false // Stop at first map object
+                }
+                None => true,
+            },
+        );
+
+        if let Some(id) = object_to_remove {
+            self.remove_map_object(id);
+        }
+    }
+
+    pub fn update(&mut self, current_time: f64) {
+        if self.is_running && self.fast_forwarding {
+            self.fast_forward_steps(current_time, FAST_FORWARD_STEPS, MAX_RACE_TIME);
+        } else if self.is_running && !self.paused {
+            // Ease into / out of slow-motion when a close finish is brewing
+            let target = if self.slowmo_enabled && self.close_call_pending() {
+                photo_finish::SLOWMO_SCALE
+            } else {
+                1.0
+            };
+            self.slowmo += (target - self.slowmo) * 0.2;
+
+            let speed = self.time_scale * self.slowmo;
+            self.step_accumulator += SUBSTEPS_PER_FRAME as f32 * speed;
+            while self.step_accumulator >= 1.0 {
+                self.step_accumulator -= 1.0;
+                self.step_physics(current_time);
+            }
+
+            self.update_standings(current_time);
+            self.spawn_trails(); // NEW: Trail Effect
+            self.spawn_portal_sparks();
+            self.update_particles(STEP_DT * SUBSTEPS_PER_FRAME as f32 * speed);
+        }
+    }
+
+    /// Advance a paused race by a fixed number of physics steps.
+    pub fn single_step(&mut self, steps: u32, current_time: f64) {
+        if !self.is_running {
+            return;
+        }
+        for _ in 0..steps {
+            self.step_physics(current_time);
+        }
+        self.update_standings(current_time);
+        self.update_particles(STEP_DT * steps as f32);
+    }
+
+    /// Every ball has left the board.
+    pub fn race_finished(&self) -> bool {
+        self.balls.is_empty() && !self.finished_balls.is_empty()
+    }
+
+    /// A race is on the board and not over yet. Its commitment covers the
+    /// map as it started, so the map can't change until it's done.
+    pub fn race_in_progress(&self) -> bool {
+        self.is_running && !self.race_finished()
+    }
+
+    /// Start running the rest of the race without rendering or effects,
+    /// `FAST_FORWARD_STEPS` per `update` so the window keeps drawing.
+    pub fn start_fast_forward(&mut self) {
+        if self.is_running {
+            self.fast_forwarding = true;
+            self.paused = false;
+        }
+    }
+
+    /// Run the rest of the race in one go (headless replays).
+    /// Gives up after `max_race_time` seconds in case a ball is stuck.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))] // Only the CLI replays in one go
+    pub fn fast_forward(&mut self, current_time: f64, max_race_time: f64) {
+        if !self.is_running {
+            return;
+        }
+        while !self.fast_forward_steps(current_time, FAST_FORWARD_STEPS, max_race_time) {}
+    }
+
+    /// Up to `steps` more steps of fast-forward. True once the race is over
+    /// (or out of time), with the effects cleared and fast-forward ended.
+    fn fast_forward_steps(&mut self, current_time: f64, steps: u32, max_race_time: f64) -> bool {
+        let headless = std::mem::replace(&mut self.headless, true);
+        let over = |state: &Self| state.race_finished() || state.sim_time >= max_race_time;
+        for _ in 0..steps {
+            if over(self) {
+                break;
+            }
+            self.step_physics(current_time);
+        }
+        self.headless = headless;
+        self.update_standings(current_time);
+
+        let done = over(self);
+        if done {
+            self.particles.clear();
+            self.fast_forwarding = false;
+        }
+        done
+    }
+
+    fn step_physics(&mut self, current_time: f64) {
+        // Safety Clamp: Limit max velocity to prevent physics explosions (tunneling/crashes)
+        let max_speed = self.map.physics.max_speed;
+        for (_handle, rb) in self.physics.rigid_body_set.iter_mut() {
+            if rb.is_dynamic() {
+                let vel = *rb.linvel();
+                let speed_sq = vel.magnitude_squared();
+                if speed_sq > max_speed * max_speed {
+                    let speed = speed_sq.sqrt();
+                    let scale = max_speed / speed;
+                    rb.set_linvel(vel * scale, true);
+                }
+            }
+        }
+
+        self.drive_kinematics(self.sim_time + STEP_DT as f64);
+        self.update_traps(self.sim_time + STEP_DT as f64);
+        self.reset_drop_banks();
+        self.update_tulips(self.sim_time + STEP_DT as f64);
+        self.apply_fields();
+        self.apply_zones();
+        self.physics.step();
+        self.sim_time += STEP_DT as f64;
+        self.check_finished_balls(current_time);
+        self.handle_collisions(current_time);
+        self.check_last_standing(current_time);
+    }
+
+    /// Switch lasers on and off on their schedule for race time `t`.
+    fn update_traps(&mut self, t: f64) {
+        for object in &self.map.objects {
+            let MapElement::Trap {
+                trap: TrapKind::Laser { on, off, .. },
+                ..
+            } = object.element
+            else {
+                continue;
+            };
+            let firing = motion::laser_on(on, off, t);
+            let Some(handles) = self.element_handles.get(&object.id) else {
+                continue;
+            };
+            for &handle in &handles.colliders {
+                if let Some(collider) = self.physics.collider_set.get_mut(handle) {
+                    if collider.is_enabled() != firing {
+                        collider.set_enabled(firing);
+                    }
+                }
+            }
+        }
+    }
+
+    /// In `LastStanding` mode, end the race as soon as one ball is left.
+    fn check_last_standing(&mut self, current_time: f64) {
+        if self.winning_condition != WinningCondition::LastStanding
+            || self.balls.len() != 1
+            || self.finished_balls.is_empty()
+        {
+            return;
+        }
+        self.take_off_board(0, Outcome::Survived, current_time);
+    }
+
+    /// The ball a trap just touched, if the other side of the contact is a trap.
+    fn trap_victim(&self, h1: ColliderHandle, h2: ColliderHandle) -> Option<RigidBodyHandle> {
+        let c1 = self.physics.collider_set.get(h1)?;
+        let c2 = self.physics.collider_set.get(h2)?;
+        let ball = if c1.user_data == maps::TRAP {
+            c2
+        } else if c2.user_data == maps::TRAP {
+            c1
+        } else {
+            return None;
+        };
+        let body = ball.parent()?;
+        self.balls.iter().any(|b| b.handle == body).then_some(body)
+    }
+
+    /// Remove a ball caught by a trap, with an explosion.
+    fn eliminate(&mut self, body: RigidBodyHandle, current_time: f64) {
+        let Some(i) = self.balls.iter().position(|b| b.handle == body) else {
+            return; // Already gone (caught twice in one step)
+        };
+        let color = self.balls[i].color;
+        if let Some(rb) = self.physics.rigid_body_set.get(body) {
+            let pos = *rb.translation();
+            self.spawn_explosion(pos.x, pos.y, color);
+        }
+        self.take_off_board(i, Outcome::Eliminated, current_time);
+    }
+
+    /// Move `balls[i]` to the results (not through the goal: that is
+    /// `check_finished_balls`) and out of the world.
+    fn take_off_board(&mut self, i: usize, outcome: Outcome, current_time: f64) {
+        let ball = self.balls.remove(i);
+        let (pos, vel) = self
+            .physics
+            .rigid_body_set
+            .get(ball.handle)
+            .map(|rb| (*rb.translation(), *rb.linvel()))
+            .unwrap_or_default();
+        self.zone_members.remove(&ball.handle);
+        self.captured.remove(&ball.handle);
+        self.pocketed.remove(&ball.handle);
+        self.physics.rigid_body_set.remove(
+            ball.handle,
+            &mut self.physics.island_manager,
+            &mut self.physics.collider_set,
+            &mut self.physics.impulse_joint_set,
+            &mut self.physics.multibody_joint_set,
+            true,
+        );
+        self.finished_balls.push(FinishedBall {
+            name: ball.name,
+            owner: ball.owner,
+            color: ball.color,
+            finished_at: current_time,
+            crossed_at: self.sim_time,
+            cross_x: pos.x,
+            cross_vel: [vel.x, vel.y],
+            outcome,
+        });
+    }
+
+    fn spawn_explosion(&mut self, x: f32, y: f32, color: [u8; 3]) {
+        if self.headless {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        for _ in 0..80 {
+            let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(80.0..400.0);
+            let color = match rng.gen_range(0..3) {
+                0 => color,
+                1 => [255, 140, 0], // Fire
+                _ => [255, 255, 200],
+            };
+            self.particles.push(Particle {
+                x,
+                y,
+                vx: angle.cos() * speed,
+                vy: angle.sin() * speed,
+                life: rng.gen_range(0.5..1.2),
+                color,
+            });
+        }
+    }
+
+    /// Final placings so far, one per owner, as indices into
+    /// `finished_balls` (the ball that places each owner), best first.
+    pub fn ranking(&self) -> Vec<usize> {
+        let balls = self.ball_ranking();
+        let mut placed = Vec::new();
+        let mut ranking = Vec::new();
+        let ordered: Box<dyn Iterator<Item = usize>> = match self.clone_rank {
+            CloneRank::Best => Box::new(balls.into_iter()),
+            CloneRank::Worst => Box::new(balls.into_iter().rev()),
+        };
+        for i in ordered {
+            let owner = self.finished_balls[i].owner;
+            if !placed.contains(&owner) {
+                placed.push(owner);
+                ranking.push(i);
+            }
+        }
+        if self.clone_rank == CloneRank::Worst {
+            ranking.reverse();
+        }
+        ranking
+    }
+
+    /// Every ball off the board so far as indices into `finished_balls`,
+    /// best first. Eliminated balls rank below every finisher, the last one
+    /// out best.
+    fn ball_ranking(&self) -> Vec<usize> {
+        let indices = 0..self.finished_balls.len();
+        if self.winning_condition == WinningCondition::LastStanding {
+            // Whoever stayed on the board longest
+            return indices.rev().collect();
+        }
+        let (mut finished, out): (Vec<usize>, Vec<usize>) =
+            indices.partition(|&i| self.finished_balls[i].outcome == Outcome::Finished);
+        if self.winning_condition == WinningCondition::Last {
+            finished.reverse();
+        }
+        finished.extend(out.into_iter().rev());
+        finished
+    }
+
+    /// Index into `finished_balls` of the winner, once it is decided: in
+    /// `First` mode the first owner through the goal (with `Worst`, the
+    /// first whose balls are all through), otherwise (or if nobody finished)
+    /// the top of `ranking` once the race is over.
+    pub fn winner(&self) -> Option<usize> {
+        if self.winning_condition == WinningCondition::First {
+            let decided = |i: usize| {
+                let owner = self.finished_balls[i].owner;
+                self.clone_rank == CloneRank::Best
+                    || !self.balls.iter().any(|b| b.owner == owner)
+                        && self.finished_balls.iter().enumerate().all(|(j, b)| {
+                            b.owner != owner || j <= i && b.outcome == Outcome::Finished
+                        })
+            };
+            if let Some(i) = (0..self.finished_balls.len())
+                .find(|&i| self.finished_balls[i].outcome == Outcome::Finished && decided(i))
+            {
+                return Some(i);
+            }
+        }
+        if self.race_finished() {
+            self.ranking().first().copied()
+        } else {
+            None
+        }
+    }
+
+    /// Force fields: pull/push balls inside their radius, and let black holes
+    /// capture and release balls. Runs right before each physics step.
+    fn apply_fields(&mut self) {
+        let fields: Vec<(u32, f32, f32, f32, f32, FieldKind)> = self
+            .map
+            .objects
+            .iter()
+            .filter_map(|o| match o.element {
+                MapElement::Field {
+                    x,
+                    y,
+                    radius,
+                    strength,
+                    kind,
+                } => Some((o.id, x, y, radius, strength, kind)),
+                _ => None,
+            })
+            .collect();
+
+        // Spit out balls whose time is up (or whose black hole was erased).
+        // Sorted so the race seed decides each ball's direction.
+        let mut due: Vec<RigidBodyHandle> = self
+            .captured
+            .iter()
+            .filter(|(_, c)| {
+                self.sim_time >= c.release_at || !fields.iter().any(|f| f.0 == c.object)
+            })
+            .map(|(&handle, _)| handle)
+            .collect();
+        due.sort_by_key(|handle| handle.into_raw_parts());
+        for handle in due {
+            self.captured.remove(&handle);
+            if let Some(rb) = self.physics.rigid_body_set.get_mut(handle) {
+                let angle: f32 = self.rng.gen_range(0.0..std::f32::consts::TAU);
+                rb.set_enabled(true);
+                rb.set_linvel(
+                    vector![angle.cos(), angle.sin()] * BLACK_HOLE_EJECT_SPEED,
+                    true,
+                );
+            }
+            self.black_hole_cooldowns
+                .insert(handle, self.sim_time + BLACK_HOLE_COOLDOWN);
+        }
+
+        if fields.is_empty() {
+            return;
+        }
+        for ball in &self.balls {
+            if self.captured.contains_key(&ball.handle) {
+                continue;
+            }
+            let Some(rb) = self.physics.rigid_body_set.get_mut(ball.handle) else {
+                continue;
+            };
+            for &(id, x, y, radius, strength, kind) in &fields {
+                let offset = vector![x, y] - *rb.translation();
+                let dist = offset.magnitude();
+                if dist >= radius || dist < f32::EPSILON {
+                    continue;
+                }
+
+                let cooling = self
+                    .black_hole_cooldowns
+                    .get(&ball.handle)
+                    .is_some_and(|&until| self.sim_time < until);
+                if kind == FieldKind::BlackHole && dist < maps::BLACK_HOLE_CORE_RADIUS && !cooling {
+                    rb.set_translation(vector![x, y], true);
+                    rb.set_linvel(vector![0.0, 0.0], true);
+                    rb.set_enabled(false);
+                    self.captured.insert(
+                        ball.handle,
+                        Capture {
+                            object: id,
+                            release_at: self.sim_time + BLACK_HOLE_HOLD,
+                        },
+                    );
+                    break;
+                }
+
+                // Linear falloff: full strength at the centre, none at the edge
+                let accel = match kind {
+                    FieldKind::Repulsor => -strength,
+                    FieldKind::Attractor | FieldKind::BlackHole => strength,
+                } * (1.0 - dist / radius);
+                let vel = *rb.linvel() + offset / dist * accel * STEP_DT;
+                rb.set_linvel(vel, true);
+            }
+        }
+    }
+
+    /// Zone effects for the balls inside them: wind pushes, mud adds damping,
+    /// ice drops friction and damping. Balls outside get their normal values
+    /// back. (Boosts are one-off kicks, see `handle_collisions`.)
+    fn apply_zones(&mut self) {
+        let base_damping = self.map.physics.ball_linear_damping;
+        for ball in &self.balls {
+            let mut push = vector![0.0, 0.0];
+            let mut damping = base_damping;
+            let mut ice = false;
+            for id in self.zone_members.get(&ball.handle).into_iter().flatten() {
+                let Some(&MapElement::Zone {
+                    kind,
+                    strength,
+                    angle,
+                    ..
+                }) = self
+                    .map
+                    .objects
+                    .iter()
+                    .find(|o| o.id == *id)
+                    .map(|o| &o.element)
+                else {
+                    continue;
+                };
+                match kind {
+                    ZoneKind::Wind => push += zone_direction(angle) * strength,
+                    ZoneKind::Mud => damping += strength,
+                    ZoneKind::Ice => ice = true,
+                    ZoneKind::Boost => {}
+                }
+            }
+            if ice {
+                damping = 0.0;
+            }
+            // Ball friction is 0; `Min` makes the contact frictionless too
+            let friction_rule = if ice {
+                CoefficientCombineRule::Min
+            } else {
+                CoefficientCombineRule::Average
+            };
+
+            let Some(rb) = self.physics.rigid_body_set.get_mut(ball.handle) else {
+                continue;
+            };
+            if push != vector![0.0, 0.0] {
+                rb.set_linvel(*rb.linvel() + push * STEP_DT, true);
+            }
+            if rb.linear_damping() != damping {
+                rb.set_linear_damping(damping);
+            }
+            for &collider in rb.colliders() {
+                if let Some(collider) = self.physics.collider_set.get_mut(collider) {
+                    if collider.friction_combine_rule() != friction_rule {
+                        collider.set_friction_combine_rule(friction_rule);
+                    }
+                }
             }
         }
     }
 
-    fn editor_erase(&mut self, x: f32, y: f32) {
-        let point = point![x, y];
-        self.physics
-            .query_pipeline
-            .update(&self.physics.rigid_body_set, &self.physics.collider_set);
-
-        let filter = QueryFilter::default();
-        let mut handle_to_remove = None;
+    /// Several balls bunched up right in front of the goal.
+    fn close_call_pending(&self) -> bool {
+        self.standings
+            .iter()
+            .filter(|s| s.remaining < photo_finish::SLOWMO_DISTANCE)
+            .count()
+            >= photo_finish::SLOWMO_BALLS
+    }
 
-        self.physics.query_pipeline.intersections_with_point(
+    fn update_standings(&mut self, current_time: f64) {
+        self.standings = standings::compute(
+            &self.balls,
             &self.physics.rigid_body_set,
-            &self.physics.collider_set,
-            &point,
-            filter,
-            |handle| {
-                handle_to_remove = Some(handle);
-                false // Stop at first
-            },
+            &self.race_path,
+            self.finished_balls
+                .iter()
+                .filter(|b| b.outcome == Outcome::Finished)
+                .count(),
+            &self.standings,
+            current_time,
         );
-
-        if let Some(handle) = handle_to_remove {
-            self.physics.collider_set.remove(
-                handle,
-                &mut self.physics.island_manager,
-                &mut self.physics.rigid_body_set,
-                true,
-            );
-        }
     }
 
-    pub fn update(&mut self, current_time: f64) {
-        if self.is_running {
-            // Safety Clamp: Limit max velocity to prevent physics explosions (tunneling/crashes)
-            let max_speed = 3000.0; // Increased limit for higher gravity
-            for (_handle, rb) in self.physics.rigid_body_set.iter_mut() {
-                if rb.is_dynamic() {
-                    let vel = *rb.linvel();
-                    let speed_sq = vel.magnitude_squared();
-                    if speed_sq > max_speed * max_speed {
-                        let speed = speed_sq.sqrt();
-                        let scale = max_speed / speed;
-                        rb.set_linvel(vel * scale, true);
-                    }
-                }
-            }
-
-            self.physics.step();
-            self.check_finished_balls(current_time);
-            self.handle_collisions(current_time);
-            self.spawn_trails(); // NEW: Trail Effect
-            self.update_particles();
-        }
+    /// The ball the canvas should call out: the leader in `First` mode, or the
+    /// ball closest to finishing (and so closest to losing) in the others.
+    pub fn highlighted_ball(&self) -> Option<RigidBodyHandle> {
+        self.standings.first().map(|s| s.handle)
     }
 
     fn spawn_trails(&mut self) {
+        if self.headless {
+            return;
+        }
         let mut rng = rand::thread_rng();
         // For each active ball, spawn a small trail particle
         for ball in &self.balls {
@@ -253,10 +1463,428 @@ impl GameState {
         }
     }
 
+    /// Slow swirl of sparks around both rings of every portal.
+    fn spawn_portal_sparks(&mut self) {
+        if self.headless {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        for object in &self.map.objects {
+            if let MapElement::Portal {
+                entry_x,
+                entry_y,
+                exit_x,
+                exit_y,
+                radius,
+                ..
+            } = object.element
+            {
+                let color = portal_color(object.id);
+                for (x, y) in [(entry_x, entry_y), (exit_x, exit_y)] {
+                    let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+                    let (sin, cos) = angle.sin_cos();
+                    self.particles.push(Particle {
+                        x: x + cos * radius,
+                        y: y + sin * radius,
+                        vx: -sin * 40.0,
+                        vy: cos * 40.0,
+                        life: 0.4,
+                        color,
+                    });
+                }
+            }
+        }
+    }
+
+    /// The ball body, sensor `user_data` and map object id of an event between
+    /// a ball and a map object's sensor (portal ring, zone).
+    fn sensor_contact(
+        &self,
+        h1: ColliderHandle,
+        h2: ColliderHandle,
+    ) -> Option<(RigidBodyHandle, u128, u32)> {
+        let c1 = self.physics.collider_set.get(h1)?;
+        let c2 = self.physics.collider_set.get(h2)?;
+        let (sensor, sensor_handle, ball) = if c1.is_sensor() {
+            (c1, h1, c2)
+        } else if c2.is_sensor() {
+            (c2, h2, c1)
+        } else {
+            return None;
+        };
+        let id = *self.collider_owner.get(&sensor_handle)?;
+        Some((ball.parent()?, sensor.user_data, id))
+    }
+
+    /// The collider, `user_data` and map object id of whichever side of a
+    /// contact belongs to a map object.
+    fn map_contact(
+        &self,
+        h1: ColliderHandle,
+        h2: ColliderHandle,
+    ) -> Option<(ColliderHandle, u128, u32)> {
+        [h1, h2].into_iter().find_map(|handle| {
+            let id = *self.collider_owner.get(&handle)?;
+            let collider = self.physics.collider_set.get(handle)?;
+            Some((handle, collider.user_data, id))
+        })
+    }
+
+    /// A ball hit a breakable block: crack it, or break it on the last hit.
+    fn hit_block(&mut self, id: u32) {
+        let Some(&MapElement::Block { x, y, hits, .. }) = self
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == id)
+            .map(|o| &o.element)
+        else {
+            return;
+        };
+        let damage = self.block_damage.entry(id).or_default();
+        *damage += 1;
+        if *damage >= hits {
+            self.despawn_object(id);
+            self.spawn_particles(x, y, 4.0, maps::BLOCK);
+        }
+    }
+
+    /// Sink a drop target; when its whole bank is down, schedule the pop-up.
+    fn drop_target(&mut self, collider: ColliderHandle, id: u32) {
+        if let Some(c) = self.physics.collider_set.get_mut(collider) {
+            c.set_enabled(false);
+        }
+        let Some(handles) = self.element_handles.get(&id) else {
+            return;
+        };
+        let all_down = handles.colliders.iter().all(|&h| {
+            self.physics
+                .collider_set
+                .get(h)
+                .is_some_and(|c| !c.is_enabled())
+        });
+        if all_down {
+            self.drop_bank_resets
+                .insert(id, self.sim_time + DROP_TARGET_RESET_DELAY);
+        }
+    }
+
+    /// Release pocketed balls whose time is up and swing tulip wings to
+    /// where they will be at race time `t`.
+    fn update_tulips(&mut self, t: f64) {
+        // Sorted so balls drop out in the same order every run
+        let mut due: Vec<RigidBodyHandle> = self
+            .pocketed
+            .iter()
+            .filter(|(_, c)| self.sim_time >= c.release_at)
+            .map(|(&handle, _)| handle)
+            .collect();
+        due.sort_by_key(|handle| handle.into_raw_parts());
+        for handle in due {
+            let Some(capture) = self.pocketed.remove(&handle) else {
+                continue;
+            };
+            // Out of the bottom (or just let go, if the tulip was erased)
+            let exit = self
+                .map
+                .objects
+                .iter()
+                .find(|o| o.id == capture.object)
+                .and_then(|o| match o.element {
+                    MapElement::Tulip { x, y, .. } => Some(maps::tulip_pocket(x, y)[1]),
+                    _ => None,
+                });
+            if let Some(rb) = self.physics.rigid_body_set.get_mut(handle) {
+                if let Some(exit) = exit {
+                    rb.set_translation(exit, true);
+                }
+                rb.set_linvel(vector![0.0, -TULIP_DROP_SPEED], true);
+                rb.set_enabled(true);
+            }
+        }
+        self.pose_tulips(t, false);
+    }
+
+    /// Put tulip wings where they are at race time `t`: as the next
+    /// kinematic target, or straight there with `snap`.
+    fn pose_tulips(&mut self, t: f64, snap: bool) {
+        for object in &self.map.objects {
+            let MapElement::Tulip { x, y, .. } = object.element else {
+                continue;
+            };
+            let opening = self
+                .tulip_wings
+                .get(&object.id)
+                .map_or(0.0, |&(opened, closes)| {
+                    motion::wing_opening(opened, closes, t)
+                });
+            let Some(handles) = self.element_handles.get(&object.id) else {
+                continue;
+            };
+            for (&body, side) in handles.bodies.iter().zip([-1.0, 1.0]) {
+                let pose = maps::tulip_wing(x, y, side, opening);
+                if let Some(rb) = self.physics.rigid_body_set.get_mut(body) {
+                    if snap {
+                        rb.set_position(pose, true);
+                    }
+                    rb.set_next_kinematic_position(pose);
+                }
+            }
+        }
+    }
+
+    /// A ball hit map object `id`: open the wings of every tulip it triggers.
+    fn trigger_tulips(&mut self, id: u32) {
+        let t = self.sim_time;
+        for object in &self.map.objects {
+            if let MapElement::Tulip {
+                trigger: Some(trigger),
+                ..
+            } = object.element
+            {
+                if trigger != id {
+                    continue;
+                }
+                let wings = self.tulip_wings.entry(object.id).or_insert((t, t));
+                if t >= wings.1 {
+                    // Shut (or shutting): open again from now
+                    wings.0 = t;
+                }
+                wings.1 = t + TULIP_OPEN_TIME;
+            }
+        }
+    }
+
+    /// A ball dropped into tulip `id`: hold it and shut the wings.
+    fn pocket_ball(&mut self, body: RigidBodyHandle, id: u32) {
+        let Some(&MapElement::Tulip { x, y, hold, .. }) = self
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == id)
+            .map(|o| &o.element)
+        else {
+            return;
+        };
+        if self.pocketed.contains_key(&body) || !self.balls.iter().any(|b| b.handle == body) {
+            return;
+        }
+        let Some(rb) = self.physics.rigid_body_set.get_mut(body) else {
+            return;
+        };
+        rb.set_translation(maps::tulip_pocket(x, y)[0], true);
+        rb.set_linvel(vector![0.0, 0.0], true);
+        rb.set_enabled(false);
+        self.pocketed.insert(
+            body,
+            Capture {
+                object: id,
+                release_at: self.sim_time + hold as f64,
+            },
+        );
+        let t = self.sim_time;
+        if let Some(wings) = self.tulip_wings.get_mut(&id) {
+            wings.1 = wings.1.min(t);
+        }
+    }
+
+    fn reset_drop_banks(&mut self) {
+        // Sorted so banks pop up in the same order every run
+        let mut due: Vec<u32> = self
+            .drop_bank_resets
+            .iter()
+            .filter(|(_, &at)| self.sim_time >= at)
+            .map(|(&id, _)| id)
+            .collect();
+        due.sort_unstable();
+        for id in due {
+            self.drop_bank_resets.remove(&id);
+            if let Some(handles) = self.element_handles.get(&id) {
+                for &handle in &handles.colliders {
+                    if let Some(c) = self.physics.collider_set.get_mut(handle) {
+                        c.set_enabled(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Put blocks, one-way gates, drop targets and the swinging jointed
+    /// obstacles back the way the map has them, so every race starts on the
+    /// same board.
+    fn restore_obstacles(&mut self) {
+        self.block_damage.clear();
+        self.drop_bank_resets.clear();
+        for i in 0..self.map.objects.len() {
+            let id = self.map.objects[i].id;
+            let element = self.map.objects[i].element.clone();
+            if matches!(
+                element,
+                MapElement::Block { .. }
+                    | MapElement::OneWayGate { .. }
+                    | MapElement::DropTargets { .. }
+                    | MapElement::Rope { .. }
+                    | MapElement::Chain { .. }
+                    | MapElement::Pendulum { .. }
+                    | MapElement::Wheel { .. }
+            ) {
+                self.despawn_object(id);
+                self.build_object(id, &element);
+            }
+        }
+    }
+
+    /// Kick a ball entering a boost zone along the zone's arrow.
+    fn boost(&mut self, body: RigidBodyHandle, zone_id: u32) {
+        let Some(&MapElement::Zone {
+            kind: ZoneKind::Boost,
+            strength,
+            angle,
+            ..
+        }) = self
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == zone_id)
+            .map(|o| &o.element)
+        else {
+            return;
+        };
+        if let Some(rb) = self.physics.rigid_body_set.get_mut(body) {
+            rb.set_linvel(*rb.linvel() + zone_direction(angle) * strength, true);
+        }
+    }
+
+    /// Move a ball from the portal's entry to its exit, keeping its speed.
+    fn teleport(&mut self, body: RigidBodyHandle, portal_id: u32) {
+        if self
+            .portal_cooldowns
+            .get(&body)
+            .is_some_and(|&until| self.sim_time < until)
+        {
+            return;
+        }
+        let Some(&MapElement::Portal {
+            entry_x,
+            entry_y,
+            exit_x,
+            exit_y,
+            rotation,
+            ..
+        }) = self
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == portal_id)
+            .map(|o| &o.element)
+        else {
+            return;
+        };
+        let Some(rb) = self.physics.rigid_body_set.get_mut(body) else {
+            return;
+        };
+
+        let vel = *rb.linvel();
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        rb.set_translation(vector![exit_x, exit_y], true);
+        rb.set_linvel(
+            vector![vel.x * cos - vel.y * sin, vel.x * sin + vel.y * cos],
+            true,
+        );
+        self.portal_cooldowns
+            .insert(body, self.sim_time + PORTAL_COOLDOWN);
+
+        let color = portal_color(portal_id);
+        self.spawn_portal_burst(entry_x, entry_y, color);
+        self.spawn_portal_burst(exit_x, exit_y, color);
+    }
+
+    fn spawn_portal_burst(&mut self, x: f32, y: f32, color: [u8; 3]) {
+        if self.headless {
+            return;
+        }
+        for i in 0..24 {
+            let angle = i as f32 / 24.0 * std::f32::consts::TAU;
+            self.particles.push(Particle {
+                x,
+                y,
+                vx: angle.cos() * 150.0,
+                vy: angle.sin() * 150.0,
+                life: 0.6,
+                color,
+            });
+        }
+    }
+
     fn handle_collisions(&mut self, current_time: f64) {
         let events = self.physics.drain_collision_events();
+        let mut teleports = Vec::new();
+        let mut boosts = Vec::new();
+        let mut block_hits = Vec::new();
+        let mut drops = Vec::new();
+        let mut eliminations = Vec::new();
+        let mut splits = Vec::new();
+        let mut pockets = Vec::new();
+        let mut contacts = Vec::new();
         for event in events {
+            if let CollisionEvent::Stopped(h1, h2, _flags) = event {
+                match self.sensor_contact(h1, h2) {
+                    Some((body, maps::ZONE, id)) => {
+                        if let Some(zones) = self.zone_members.get_mut(&body) {
+                            zones.retain(|&zone| zone != id);
+                        }
+                    }
+                    Some((_, maps::ONE_WAY, _)) => {
+                        // First ball is through: shut the gate behind it
+                        let gate = self.map_contact(h1, h2).map(|(handle, ..)| handle);
+                        if let Some(c) = gate.and_then(|h| self.physics.collider_set.get_mut(h)) {
+                            c.set_sensor(false);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
             if let CollisionEvent::Started(h1, h2, _flags) = event {
+                if let Some((handle, _, id)) = self.map_contact(h1, h2) {
+                    let sensor = self.physics.collider_set[handle].is_sensor();
+                    contacts.push((id, sensor));
+                }
+                if let Some(body) = self.trap_victim(h1, h2) {
+                    eliminations.push(body);
+                    continue;
+                }
+
+                // Map object sensors: portals teleport from the entry ring,
+                // zones track who is inside. Neither sparks.
+                if let Some((body, sensor, id)) = self.sensor_contact(h1, h2) {
+                    match sensor {
+                        maps::PORTAL_ENTRY => teleports.push((body, id)),
+                        maps::TULIP_POCKET => pockets.push((body, id)),
+                        maps::ZONE => {
+                            self.zone_members.entry(body).or_default().push(id);
+                            boosts.push((body, id));
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // Breakables count the hit, then spark like everything else
+                match self.map_contact(h1, h2) {
+                    Some((_, maps::BLOCK, id)) => block_hits.push(id),
+                    Some((handle, maps::DROP_TARGET, id)) => drops.push((handle, id)),
+                    Some((handle, maps::SPLITTER, id)) => {
+                        let ball = if handle == h1 { h2 } else { h1 };
+                        if let Some(body) =
+                            self.physics.collider_set.get(ball).and_then(|c| c.parent())
+                        {
+                            splits.push((body, id));
+                        }
+                    }
+                    _ => {}
+                }
+
                 // Record hit time for flashing
                 self.hit_times.insert(h1, current_time);
                 self.hit_times.insert(h2, current_time);
@@ -298,9 +1926,182 @@ impl GameState {
                 self.spawn_particles(cx, cy, intensity, type_id);
             }
         }
+
+        // After the event loop: moving bodies while reading events is messy
+        for id in block_hits {
+            self.hit_block(id);
+        }
+        for (collider, id) in drops {
+            self.drop_target(collider, id);
+        }
+        for (body, id) in boosts {
+            self.boost(body, id);
+        }
+        for (body, id) in teleports {
+            self.teleport(body, id);
+        }
+        for (body, id) in splits {
+            self.split(body, id);
+        }
+        for &(id, _) in &contacts {
+            self.trigger_tulips(id);
+        }
+        for (body, id) in pockets {
+            self.pocket_ball(body, id);
+        }
+        for body in eliminations {
+            self.eliminate(body, current_time);
+        }
+        self.run_links(&contacts);
+    }
+
+    /// Fire the map's links for this step: `contacts` are the map objects
+    /// balls touched (and whether that was a sensor), in event order.
+    fn run_links(&mut self, contacts: &[(u32, bool)]) {
+        if self.map.links.is_empty() {
+            return;
+        }
+        // Hit counts before and after this step, per object
+        let mut hits_before = HashMap::new();
+        for &(id, sensor) in contacts {
+            if !sensor {
+                let hits = self.link_hits.entry(id).or_default();
+                hits_before.entry(id).or_insert(*hits);
+                *hits += 1;
+            }
+        }
+
+        let finished = self
+            .finished_balls
+            .iter()
+            .filter(|b| b.outcome == Outcome::Finished)
+            .count() as u32;
+        let mut actions = Vec::new();
+        for (i, link) in self.map.links.iter().enumerate() {
+            let fires = match link.trigger {
+                Trigger::Entered { object } => contacts
+                    .iter()
+                    .filter(|&&(id, sensor)| id == object && sensor)
+                    .count(),
+                Trigger::Hits { object, count } => {
+                    let count = count.max(1);
+                    let before = hits_before.get(&object).copied().unwrap_or(0);
+                    let after = self.link_hits.get(&object).copied().unwrap_or(0);
+                    (after / count - before / count) as usize
+                }
+                Trigger::Finished { count } => {
+                    usize::from(finished >= count && self.links_fired.insert(i))
+                }
+                Trigger::Time { seconds } => {
+                    usize::from(self.sim_time >= seconds as f64 && self.links_fired.insert(i))
+                }
+            };
+            for _ in 0..fires {
+                actions.push(link.action.clone());
+            }
+        }
+        for action in actions {
+            self.run_action(action);
+        }
+    }
+
+    fn run_action(&mut self, action: Action) {
+        let t = self.sim_time;
+        match action {
+            Action::OpenGate { object, seconds } => {
+                let gate = self.forced_gates.entry(object).or_insert((t, t));
+                if t >= gate.1 {
+                    // Shut (or shutting): open again from now
+                    gate.0 = t;
+                }
+                gate.1 = gate.1.max(t + seconds as f64);
+            }
+            Action::Reverse { object } => {
+                let Some(handles) = self.element_handles.get(&object) else {
+                    return;
+                };
+                let joints: Vec<ImpulseJointHandle> = handles
+                    .bodies
+                    .iter()
+                    .flat_map(|&body| self.physics.impulse_joint_set.attached_joints(body))
+                    .filter(|(.., joint)| joint.data.motor(JointAxis::AngX).is_some())
+                    .map(|(_, _, handle, _)| handle)
+                    .collect();
+                for handle in joints {
+                    if let Some(joint) = self.physics.impulse_joint_set.get_mut(handle) {
+                        let motor = joint.data.motors[JointAxis::AngX as usize];
+                        joint.data.set_motor_velocity(
+                            JointAxis::AngX,
+                            -motor.target_vel,
+                            motor.damping,
+                        );
+                    }
+                }
+                self.changed_objects.insert(object);
+            }
+            Action::SetLevel { object, level } => {
+                let (restitution, user_data) = maps::get_elasticity_props(&self.map.physics, level);
+                let Some(handles) = self.element_handles.get(&object) else {
+                    return;
+                };
+                for &handle in &handles.colliders {
+                    if let Some(collider) = self.physics.collider_set.get_mut(handle) {
+                        collider.set_restitution(restitution);
+                        collider.user_data = user_data;
+                    }
+                }
+                self.changed_objects.insert(object);
+            }
+            Action::Spawn { element } => {
+                let handles = maps::build_element(&mut self.physics, &self.map.physics, &element);
+                self.spawned.push(handles);
+            }
+            Action::Gravity { gravity } => {
+                self.physics.gravity = vector![0.0, gravity];
+            }
+        }
+    }
+
+    /// Undo what links did during the race.
+    fn reset_links(&mut self) {
+        self.link_hits.clear();
+        self.links_fired.clear();
+        self.forced_gates.clear();
+        for handles in std::mem::take(&mut self.spawned) {
+            self.remove_handles(handles);
+        }
+        for id in std::mem::take(&mut self.changed_objects) {
+            let Some(element) = self
+                .map
+                .objects
+                .iter()
+                .find(|o| o.id == id)
+                .map(|o| o.element.clone())
+            else {
+                continue;
+            };
+            self.despawn_object(id);
+            self.build_object(id, &element);
+        }
+        self.physics.gravity = vector![0.0, self.map.physics.gravity];
+    }
+
+    /// Add a link (from the editor), unless the same one is already there.
+    pub fn add_link(&mut self, link: Link) {
+        let exists = self.map.links.iter().any(|l| {
+            l.trigger == link.trigger
+                && l.action.index() == link.action.index()
+                && l.action.object() == link.action.object()
+        });
+        if !exists {
+            self.map.links.push(link);
+        }
     }
 
     fn spawn_particles(&mut self, x: f32, y: f32, intensity: f32, type_id: u128) {
+        if self.headless {
+            return;
+        }
         let mut rng = rand::thread_rng();
 
         // Boost counts for "Flashy" feel
@@ -309,7 +2110,7 @@ impl GameState {
         let count = ((base_count + intensity * 15.0).clamp(10.0, 100.0)) as usize;
 
         for _ in 0..count {
-            let angle: f32 = rng.gen_range(0.0..6.28);
+            let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
 
             // Speed boost
             let speed_mult = intensity.clamp(0.8, 4.0);
@@ -375,6 +2176,21 @@ impl GameState {
                         _ => [200, 255, 255], // White Cyan
                     }
                 }
+                maps::BLOCK => {
+                    // Breakable Block - Violet chips
+                    match rng.gen_range(0..2) {
+                        0 => [170, 120, 255],
+                        _ => [220, 200, 255],
+                    }
+                }
+                maps::DROP_TARGET => {
+                    // Drop Target - Orange-Red
+                    [255, 90, 60]
+                }
+                maps::SPLITTER => {
+                    // Splitter - Silver
+                    [220, 220, 240]
+                }
                 99 => {
                     // Goal - Rainbow/Victory
                     [rng.gen(), rng.gen(), rng.gen()]
@@ -403,8 +2219,7 @@ impl GameState {
         }
     }
 
-    fn update_particles(&mut self) {
-        let dt = 1.0 / 60.0; // estim
+    fn update_particles(&mut self, dt: f32) {
         for p in &mut self.particles {
             p.x += p.vx * dt;
             p.y += p.vy * dt;
@@ -415,20 +2230,34 @@ impl GameState {
     }
 
     fn check_finished_balls(&mut self, current_time: f64) {
-        let finish_y = -self.map_height / 2.0 + 50.0; // Threshold
-        let mut completed_indices = Vec::new();
+        let finish_y = maps::finish_y(self.map_height);
+        let dt = STEP_DT as f64;
+        let mut completed = Vec::new();
 
         for (i, ball) in self.balls.iter().enumerate() {
             if let Some(rb) = self.physics.rigid_body_set.get(ball.handle) {
-                if rb.translation().y < finish_y {
-                    completed_indices.push(i);
+                let pos = rb.translation();
+                if pos.y < finish_y {
+                    // Interpolate the crossing within this step from the velocity
+                    let vel = *rb.linvel();
+                    let prev_y = pos.y - vel.y * STEP_DT;
+                    let frac = if prev_y > pos.y {
+                        ((prev_y - finish_y) / (prev_y - pos.y)).clamp(0.0, 1.0) as f64
+                    } else {
+                        1.0
+                    };
+                    let crossed_at = self.sim_time - dt + frac * dt;
+                    let back = (1.0 - frac) as f32 * STEP_DT;
+                    completed.push((i, crossed_at, pos.x - vel.x * back, [vel.x, vel.y]));
                 }
             }
         }
 
-        // Process in reverse to maintain indices when removing
-        for i in completed_indices.into_iter().rev() {
+        // Remove in reverse to keep indices valid, then record in crossing order
+        let mut crossings = Vec::new();
+        for (i, crossed_at, cross_x, cross_vel) in completed.into_iter().rev() {
             let ball = self.balls.remove(i);
+            self.zone_members.remove(&ball.handle);
             // Remove from physics
             self.physics.rigid_body_set.remove(
                 ball.handle,
@@ -438,29 +2267,149 @@ impl GameState {
                 &mut self.physics.multibody_joint_set,
                 true,
             );
-
-            self.finished_balls.push(FinishedBall {
+            crossings.push(FinishedBall {
                 name: ball.name,
+                owner: ball.owner,
                 color: ball.color,
                 finished_at: current_time,
+                crossed_at,
+                cross_x,
+                cross_vel,
+                outcome: Outcome::Finished,
             });
         }
+        crossings.sort_by(|a, b| a.crossed_at.total_cmp(&b.crossed_at));
+
+        for finished in crossings {
+            self.finished_balls.push(finished);
+
+            let balls = &self.balls;
+            let bodies = &self.physics.rigid_body_set;
+            photo_finish::record(
+                &mut self.photo_finishes,
+                &self.finished_balls,
+                finish_y,
+                self.photo_finish_gap,
+                || {
+                    // Everyone else close enough to be in the picture
+                    balls
+                        .iter()
+                        .filter_map(|b| {
+                            let pos = bodies.get(b.handle)?.translation();
+                            (pos.y < finish_y + photo_finish::SLOWMO_DISTANCE).then(|| {
+                                photo_finish::PhotoBall {
+                                    color: b.color,
+                                    x: pos.x,
+                                    y: pos.y,
+                                }
+                            })
+                        })
+                        .collect()
+                },
+            );
+        }
     }
 
-    pub fn spawn_ball(&mut self, name: String) {
-        let mut rng = rand::thread_rng();
-        let x_offset = rng.gen_range(-100.0..100.0);
+    /// Start the race's randomness from `seed`, so the same seed, roster and
+    /// map run the same race. The world is rebuilt too: handles and contact
+    /// caches left over from an earlier race would otherwise nudge this one.
+    pub fn seed_race(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self.build_world();
+    }
+
+    /// Drop a new ball for `owner` (added to `owners` on first use). The
+    /// owner's first ball sets the colour for the rest: `color`, or a free
+    /// palette colour.
+    pub fn spawn_ball(
+        &mut self,
+        owner_name: &str,
+        name: String,
+        color: Option<[u8; 3]>,
+        skin: skin::Skin,
+    ) {
+        let x_offset = self.rng.gen_range(-100.0..100.0);
         let y_start = self.map_height / 2.0 - 20.0; // Near top
 
+        let owner = match self.owners.iter().position(|o| o == owner_name) {
+            Some(i) => i as u32,
+            None => {
+                self.owners.push(owner_name.to_owned());
+                self.owners.len() as u32 - 1
+            }
+        };
+        let handle = self.insert_ball_body(vector![x_offset, y_start]);
+        let color = match self.balls.iter().find(|b| b.owner == owner) {
+            Some(ball) => ball.color,
+            None => color.unwrap_or_else(|| {
+                let taken: Vec<[u8; 3]> = self.balls.iter().map(|b| b.color).collect();
+                skin::pick_color(owner_name, &taken)
+            }),
+        };
+
+        self.balls.push(Ball {
+            name,
+            owner,
+            handle,
+            color,
+            skin,
+            splits: Vec::new(),
+        });
+    }
+
+    /// A ball hit splitter `id`: fan it and a clone (same owner and colour)
+    /// out either side of its heading.
+    fn split(&mut self, body: RigidBodyHandle, id: u32) {
+        let Some(i) = self.balls.iter().position(|b| b.handle == body) else {
+            return;
+        };
+        if self.balls[i].splits.contains(&id) {
+            return;
+        }
+        self.balls[i].splits.push(id);
+        let Some(rb) = self.physics.rigid_body_set.get_mut(body) else {
+            return;
+        };
+        let pos = *rb.translation();
+        let vel = *rb.linvel();
+        let spread = SPLIT_SPREAD.to_radians();
+        rb.set_linvel(Rotation::new(spread) * vel, true);
+
+        // Side by side across the heading, so the two don't start overlapping
+        let across = if vel.norm() > f32::EPSILON {
+            vector![-vel.y, vel.x].normalize()
+        } else {
+            vector![1.0, 0.0]
+        };
+        let gap = self.map.physics.ball_radius * 2.0 + 1.0;
+        let handle = self.insert_ball_body(pos - across * gap);
+        self.physics.rigid_body_set[handle].set_linvel(Rotation::new(-spread) * vel, true);
+
+        let parent = &self.balls[i];
+        let clone = Ball {
+            name: format!("{}'", parent.name),
+            owner: parent.owner,
+            handle,
+            color: parent.color,
+            skin: parent.skin.clone(),
+            splits: parent.splits.clone(),
+        };
+        self.balls.push(clone);
+        self.spawn_particles(pos.x, pos.y, 2.0, maps::SPLITTER);
+    }
+
+    fn insert_ball_body(&mut self, position: Vector<f32>) -> RigidBodyHandle {
+        let settings = &self.map.physics;
         let rigid_body = RigidBodyBuilder::dynamic()
-            .translation(vector![x_offset, y_start])
-            .ccd_enabled(true) // Prevent tunneling
-            .linear_damping(0.1) // Air resistance stability
+            .translation(position)
+            .ccd_enabled(settings.ccd_enabled) // Prevent tunneling
+            .linear_damping(settings.ball_linear_damping) // Air resistance stability
             .build();
         let handle = self.physics.rigid_body_set.insert(rigid_body);
 
-        let collider = ColliderBuilder::ball(8.0)
-            .restitution(0.7)
+        let collider = ColliderBuilder::ball(settings.ball_radius)
+            .restitution(settings.ball_restitution)
             .friction(0.0)
             .density(1.0)
             .collision_groups(InteractionGroups::new(
@@ -473,48 +2422,21 @@ impl GameState {
             handle,
             &mut self.physics.rigid_body_set,
         );
-
-        let color = [rng.gen(), rng.gen(), rng.gen()];
-
-        self.balls.push(Ball {
-            name,
-            handle,
-            color,
-        });
+        handle
     }
 
+    /// Drop a random obstacle from the top. Not while a race is running:
+    /// it's outside the seed and the commitment.
     pub fn spawn_event_obstacle(&mut self) {
+        if self.race_in_progress() {
+            return;
+        }
         let mut rng = rand::thread_rng();
         let x_offset = rng.gen_range(-self.map_width / 2.0 + 40.0..self.map_width / 2.0 - 40.0);
         let y_start = self.map_height / 2.0 - 50.0;
 
         // 1. Random Neon Color (High Saturation/Brightness)
-        // HSV to RGB conversion simplified or just pick vibrant mix
-        let hue = rng.gen_range(0.0f32..360.0f32);
-        let s = 1.0f32;
-        let v = 1.0f32;
-
-        let c = v * s;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = v - c;
-
-        let (r_f, g_f, b_f) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        let r = ((r_f + m) * 255.0) as u128;
-        let g = ((g_f + m) * 255.0) as u128;
-        let b = ((b_f + m) * 255.0) as u128;
+        let [r, g, b] = neon_color(rng.gen_range(0.0f32..360.0f32)).map(|c| c as u128);
 
         // 2. Random Shape: 0=Circle, 1=Square, 2=Triangle, 3=Star
         let shape_id: u128 = rng.gen_range(0..4);
@@ -531,7 +2453,7 @@ impl GameState {
         // Physics Body
         let rigid_body = RigidBodyBuilder::dynamic()
             .translation(vector![x_offset, y_start])
-            .rotation(rng.gen_range(0.0..3.14))
+            .rotation(rng.gen_range(0.0..std::f32::consts::PI))
             .build();
         let handle = self.physics.rigid_body_set.insert(rigid_body);
 
@@ -579,11 +2501,32 @@ impl GameState {
     pub fn reset_map(&mut self) {
         self.balls.clear();
         self.finished_balls.clear();
-        self.physics = PhysicsEngine::new();
-        // Re-create map
+        self.owners.clear();
+        self.standings.clear();
+        self.photo_finishes.clear();
+        self.portal_cooldowns.clear();
+        self.captured.clear();
+        self.pocketed.clear();
+        self.tulip_wings.clear();
+        self.black_hole_cooldowns.clear();
+        self.zone_members.clear();
+        self.sim_time = 0.0;
+        self.step_accumulator = 0.0;
+        self.slowmo = 1.0;
+        self.paused = false;
+        self.fast_forwarding = false;
+        // Re-create map (keeping the tuned physics)
         let width = self.map_width;
         let height = self.map_height;
-        maps::create_map(&mut self.physics, width, height);
+        let physics = self.map.physics.clone();
+        self.map = MapFile::new(
+            "Random Map",
+            width,
+            height,
+            maps::generate_elements(width, height),
+        );
+        self.map.physics = physics;
+        self.build_world();
         self.is_running = false;
     }
 
@@ -597,6 +2540,29 @@ impl GameState {
 
         self.balls.clear();
         self.finished_balls.clear();
+        self.owners.clear();
+        self.standings.clear();
+        self.photo_finishes.clear();
+        self.portal_cooldowns.clear();
+        self.captured.clear();
+        self.pocketed.clear();
+        self.tulip_wings.clear();
+        self.black_hole_cooldowns.clear();
+        self.zone_members.clear();
+        self.sim_time = 0.0;
+        self.step_accumulator = 0.0;
+        self.slowmo = 1.0;
+        self.paused = false;
+        self.fast_forwarding = false;
+
+        // Event obstacles go too (spec: reset clears balls and event obstacles)
+        for (_handle, collider) in self.physics.collider_set.iter() {
+            if (collider.user_data >> 64) & 1 == 1 {
+                if let Some(parent) = collider.parent() {
+                    handles_to_remove.push(parent);
+                }
+            }
+        }
 
         // Remove bodies from physics
         for handle in handles_to_remove {
@@ -610,6 +2576,49 @@ impl GameState {
             );
         }
 
+        self.restore_obstacles();
+        self.reset_links();
+
+        // Platforms and gates back to where they start
+        for i in 0..self.map.objects.len() {
+            let id = self.map.objects[i].id;
+            let element = self.map.objects[i].element.clone();
+            self.place_kinematic(id, &element, 0.0);
+        }
+        self.pose_tulips(0.0, true);
+
         self.is_running = false;
     }
 }
+
+/// Fully saturated colour for a hue in degrees (0-360).
+fn neon_color(hue: f32) -> [u8; 3] {
+    let c = 1.0;
+    let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+
+    let (r, g, b) = if hue < 60.0 {
+        (c, x, 0.0)
+    } else if hue < 120.0 {
+        (x, c, 0.0)
+    } else if hue < 180.0 {
+        (0.0, c, x)
+    } else if hue < 240.0 {
+        (0.0, x, c)
+    } else if hue < 300.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+/// Shared colour of a portal's two rings, so pairs are easy to match up.
+pub fn portal_color(id: u32) -> [u8; 3] {
+    neon_color((id as f32 * 137.5) % 360.0)
+}
+
+/// Unit vector for a zone `angle` in degrees (0 = right, 90 = up).
+pub fn zone_direction(angle: f32) -> Vector<f32> {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    vector![cos, sin]
+}
@@ -0,0 +1,183 @@
+use crate::game::map_file::{Easing, MapElement, PlatformPath, TrapKind};
+use rapier2d::prelude::*;
+
+// Time a gate takes to slide fully open or shut.
+const GATE_SLIDE_TIME: f32 = 0.4;
+
+// Time tulip wings take to swing open or shut.
+const WING_SWING_TIME: f64 = 0.15;
+
+// Crusher cycle: slam down, rest at the bottom, then rise for the rest.
+const CRUSHER_SLAM: f32 = 0.1;
+const CRUSHER_REST: f32 = 0.25;
+
+/// Where a kinematic map element is at race time `t`, or `None` for
+/// elements that don't move on their own. Pure function of `t`, so every
+/// race replays the same motion from the start.
+pub fn kinematic_pose(element: &MapElement, t: f64) -> Option<Isometry<f32>> {
+    match *element {
+        MapElement::Platform {
+            angle,
+            ref path,
+            period,
+            easing,
+            ..
+        } => {
+            let u = cycle(t, period);
+            let [x, y] = path_point(path, easing, u);
+            Some(Isometry::new(vector![x, y], angle.to_radians()))
+        }
+        MapElement::Gate {
+            x1,
+            y1,
+            x2,
+            y2,
+            closed,
+            open,
+            offset,
+        } => Some(gate_pose(
+            x1,
+            y1,
+            x2,
+            y2,
+            gate_opening(closed, open, offset, t),
+        )),
+        MapElement::Trap {
+            x,
+            y,
+            trap: TrapKind::Crusher { drop, period, .. },
+        } => Some(Isometry::translation(
+            x,
+            y - drop * crusher_depth(cycle(t, period)),
+        )),
+        _ => None,
+    }
+}
+
+/// Points along a platform path over one cycle, for editor previews.
+pub fn path_preview(path: &PlatformPath, easing: Easing, samples: usize) -> Vec<[f32; 2]> {
+    (0..=samples)
+        .map(|i| path_point(path, easing, i as f32 / samples as f32))
+        .collect()
+}
+
+/// How far open a gate is at race time `t` (0 = shut, 1 = open).
+pub fn gate_opening(closed: f32, open: f32, offset: f32, t: f64) -> f32 {
+    let t = t as f32 - offset;
+    let cycle = closed + open;
+    if t < 0.0 || cycle <= 0.0 {
+        return 0.0;
+    }
+    let t = t % cycle;
+    let slide = GATE_SLIDE_TIME.min(open / 2.0).max(f32::EPSILON);
+    if t < closed {
+        0.0
+    } else if t < closed + slide {
+        ease((t - closed) / slide, Easing::EaseInOut)
+    } else if t < cycle - slide {
+        1.0
+    } else {
+        ease((cycle - t) / slide, Easing::EaseInOut)
+    }
+}
+
+/// Whether a laser with this on/off schedule is firing at race time `t`.
+pub fn laser_on(on: f32, off: f32, t: f64) -> bool {
+    let cycle = (on + off) as f64;
+    cycle <= 0.0 || t % cycle < on as f64
+}
+
+/// How far open (0 = shut, 1 = wide) tulip wings are at race time `t`, if
+/// they opened at `opened_at` and shut (or will shut) at `closes_at`.
+pub fn wing_opening(opened_at: f64, closes_at: f64, t: f64) -> f32 {
+    held_open(opened_at, closes_at, WING_SWING_TIME, t)
+}
+
+/// How far open a gate held open by a link from `opened_at` to
+/// `closes_at` is at race time `t`.
+pub fn forced_gate_opening(opened_at: f64, closes_at: f64, t: f64) -> f32 {
+    held_open(opened_at, closes_at, GATE_SLIDE_TIME as f64, t)
+}
+
+/// Pose of a gate `opened` (0-1) of the way along its slide.
+pub fn gate_pose(x1: f32, y1: f32, x2: f32, y2: f32, opened: f32) -> Isometry<f32> {
+    // Slides along itself towards (x1, y1), so it ends up beyond that end
+    let x = (x1 + x2) / 2.0 - (x2 - x1) * opened;
+    let y = (y1 + y2) / 2.0 - (y2 - y1) * opened;
+    Isometry::new(vector![x, y], (y2 - y1).atan2(x2 - x1))
+}
+
+/// How far down (0 = top, 1 = bottom) a crusher is `u` through its cycle.
+fn crusher_depth(u: f32) -> f32 {
+    if u < CRUSHER_SLAM {
+        (u / CRUSHER_SLAM).powi(2) // Accelerating slam
+    } else if u < CRUSHER_REST {
+        1.0
+    } else {
+        1.0 - ease((u - CRUSHER_REST) / (1.0 - CRUSHER_REST), Easing::EaseInOut)
+    }
+}
+
+/// Eased swing open from `opened_at` and back shut from `closes_at`, each
+/// taking `swing` seconds.
+fn held_open(opened_at: f64, closes_at: f64, swing: f64, t: f64) -> f32 {
+    let opening = ((t - opened_at) / swing).clamp(0.0, 1.0) as f32;
+    let closing = ((t - closes_at) / swing).clamp(0.0, 1.0) as f32;
+    ease(opening, Easing::EaseInOut).min(1.0 - ease(closing, Easing::EaseInOut))
+}
+
+/// Fraction (0-1) of the current cycle at race time `t`.
+fn cycle(t: f64, period: f32) -> f32 {
+    if period <= 0.0 {
+        return 0.0;
+    }
+    (t / period as f64).fract() as f32
+}
+
+fn ease(u: f32, easing: Easing) -> f32 {
+    match easing {
+        Easing::Linear => u,
+        Easing::EaseInOut => 0.5 - 0.5 * (u * std::f32::consts::PI).cos(),
+    }
+}
+
+/// Position on a path `u` (0-1) of the way through a cycle.
+fn path_point(path: &PlatformPath, easing: Easing, u: f32) -> [f32; 2] {
+    match *path {
+        PlatformPath::Slider { x1, y1, x2, y2 } => {
+            legs_point(&[[x1, y1], [x2, y2]], false, easing, u)
+        }
+        PlatformPath::Orbit { x, y, radius } => {
+            // Easing applies to each half turn
+            let half = (u * 2.0).floor();
+            let a = (half + ease(u * 2.0 - half, easing)) * std::f32::consts::PI;
+            [x + radius * a.cos(), y + radius * a.sin()]
+        }
+        PlatformPath::Waypoints { ref points, looped } => legs_point(points, looped, easing, u),
+    }
+}
+
+/// Walk the legs between waypoints, each taking the same time: there and back
+/// again, or round the loop. Easing applies per leg.
+fn legs_point(points: &[[f32; 2]], looped: bool, easing: Easing, u: f32) -> [f32; 2] {
+    let n = points.len();
+    if n < 2 {
+        return points.first().copied().unwrap_or([0.0, 0.0]);
+    }
+    let legs = if looped { n } else { 2 * (n - 1) };
+    let f = u * legs as f32;
+    let leg = (f.floor() as usize).min(legs - 1);
+    let local = ease(f - leg as f32, easing);
+
+    let (from, to) = if looped {
+        (leg, (leg + 1) % n)
+    } else if leg < n - 1 {
+        (leg, leg + 1)
+    } else {
+        // On the way back
+        let back = leg - (n - 1);
+        (n - 1 - back, n - 2 - back)
+    };
+    let (a, b) = (points[from], points[to]);
+    [a[0] + (b[0] - a[0]) * local, a[1] + (b[1] - a[1]) * local]
+}
@@ -0,0 +1,77 @@
+use crate::game::{FinishedBall, Outcome};
+
+// Slow-motion kicks in when this many balls are within SLOWMO_DISTANCE of the goal.
+pub const SLOWMO_BALLS: usize = 2;
+pub const SLOWMO_DISTANCE: f32 = 90.0;
+pub const SLOWMO_SCALE: f32 = 0.25;
+
+// Default gap (seconds of race time) below which two finishes count as close.
+pub const DEFAULT_GAP: f64 = 0.1;
+
+/// A ball still on the board when the photo was taken.
+pub struct PhotoBall {
+    pub color: [u8; 3],
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A frozen frame of a close finish.
+pub struct PhotoFinish {
+    pub finish_y: f32,
+    pub entries: Vec<usize>, // Indices into `finished_balls`, in crossing order
+    pub others: Vec<PhotoBall>,
+}
+
+impl PhotoFinish {
+    /// Race time of the last crossing in the group; the photo is "taken" then.
+    pub fn shutter_time(&self, finished: &[FinishedBall]) -> f64 {
+        self.entries
+            .last()
+            .map(|&i| finished[i].crossed_at)
+            .unwrap_or(0.0)
+    }
+
+    /// Where a finished ball was at the shutter time, extrapolated from its
+    /// crossing point and velocity.
+    pub fn position_of(&self, ball: &FinishedBall, shutter: f64) -> (f32, f32) {
+        let dt = (shutter - ball.crossed_at) as f32;
+        (
+            ball.cross_x + ball.cross_vel[0] * dt,
+            self.finish_y + ball.cross_vel[1] * dt,
+        )
+    }
+}
+
+/// Group the newest finisher with the previous one when they crossed within `gap`.
+pub fn record(
+    photo_finishes: &mut Vec<PhotoFinish>,
+    finished: &[FinishedBall],
+    finish_y: f32,
+    gap: f64,
+    others: impl FnOnce() -> Vec<PhotoBall>,
+) {
+    let n = finished.len();
+    if n < 2
+        || finished[n - 2..]
+            .iter()
+            .any(|b| b.outcome != Outcome::Finished)
+        || finished[n - 1].crossed_at - finished[n - 2].crossed_at >= gap
+    {
+        return;
+    }
+
+    // Extend the running group if the previous finisher is already in it
+    if let Some(photo) = photo_finishes.last_mut() {
+        if photo.entries.last() == Some(&(n - 2)) {
+            photo.entries.push(n - 1);
+            photo.others = others();
+            return;
+        }
+    }
+
+    photo_finishes.push(PhotoFinish {
+        finish_y,
+        entries: vec![n - 2, n - 1],
+        others: others(),
+    });
+}
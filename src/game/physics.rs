@@ -1,5 +1,10 @@
 use rapier2d::prelude::*;
 
+// Fixed physics step. A rendered frame at 1x speed runs SUBSTEPS_PER_FRAME of
+// these, so playback speed never changes the simulated outcome.
+pub const STEP_DT: f32 = 1.0 / 240.0;
+pub const SUBSTEPS_PER_FRAME: u32 = 4;
+
 pub struct PhysicsEngine {
     pub gravity: Vector<f32>,
     pub integration_parameters: IntegrationParameters,
@@ -17,6 +22,7 @@ pub struct PhysicsEngine {
     #[allow(dead_code)]
     pub contact_force_recv: crossbeam_channel::Receiver<ContactForceEvent>,
     pub event_handler: ChannelEventCollector,
+    pub step_time_ms: f64, // Wall-clock cost of the last step (debug readout)
 }
 
 impl PhysicsEngine {
@@ -26,9 +32,14 @@ impl PhysicsEngine {
         let (contact_force_send, contact_force_recv) = crossbeam_channel::unbounded();
         let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
 
+        let integration_parameters = IntegrationParameters {
+            dt: STEP_DT,
+            ..Default::default()
+        };
+
         Self {
             gravity,
-            integration_parameters: IntegrationParameters::default(),
+            integration_parameters,
             island_manager: IslandManager::new(),
             broad_phase: BroadPhaseMultiSap::new(),
             narrow_phase: NarrowPhase::new(),
@@ -42,10 +53,12 @@ impl PhysicsEngine {
             event_handler,
             collision_recv,
             contact_force_recv,
+            step_time_ms: 0.0,
         }
     }
 
     pub fn step(&mut self) {
+        let started = web_time::Instant::now();
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -61,6 +74,15 @@ impl PhysicsEngine {
             &(),
             &self.event_handler,
         );
+        self.step_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+    }
+
+    /// Contact pairs currently touching (debug readout).
+    pub fn active_contact_count(&self) -> usize {
+        self.narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+            .count()
     }
 
     pub fn drain_collision_events(&mut self) -> Vec<CollisionEvent> {
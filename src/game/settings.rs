@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// Tunable physics parameters. Saved inside the map file so a map always
+/// plays with the physics it was tuned for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSettings {
+    pub gravity: f32,
+    pub max_speed: f32, // Safety clamp against tunneling/explosions
+    pub ball_radius: f32,
+    pub ball_restitution: f32,
+    pub ball_linear_damping: f32,
+    pub elasticity_levels: [f32; 5], // Restitution of pin levels 1-5
+    pub solver_iterations: usize,
+    pub ccd_enabled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Preset {
+    Floaty,
+    Standard,
+    Chaos,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Floaty, Preset::Standard, Preset::Chaos];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Floaty => "Floaty",
+            Preset::Standard => "Standard",
+            Preset::Chaos => "Chaos",
+        }
+    }
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self::preset(Preset::Standard)
+    }
+}
+
+impl PhysicsSettings {
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Standard => Self {
+                gravity: -68.67,
+                max_speed: 3000.0,
+                ball_radius: 8.0,
+                ball_restitution: 0.7,
+                ball_linear_damping: 0.1,
+                elasticity_levels: [0.8, 1.0, 1.5, 2.0, 3.0],
+                solver_iterations: 4,
+                ccd_enabled: true,
+            },
+            Preset::Floaty => Self {
+                gravity: -30.0,
+                max_speed: 1500.0,
+                ball_radius: 8.0,
+                ball_restitution: 0.8,
+                ball_linear_damping: 0.4,
+                elasticity_levels: [0.6, 0.8, 1.1, 1.4, 1.8],
+                solver_iterations: 4,
+                ccd_enabled: true,
+            },
+            Preset::Chaos => Self {
+                gravity: -120.0,
+                max_speed: 5000.0,
+                ball_radius: 7.0,
+                ball_restitution: 0.95,
+                ball_linear_damping: 0.0,
+                elasticity_levels: [1.0, 1.5, 2.5, 3.5, 5.0],
+                solver_iterations: 4,
+                ccd_enabled: true,
+            },
+        }
+    }
+
+    /// Restitution for an elasticity level (1-5); anything else is level 1.
+    pub fn elasticity(&self, level: u8) -> f32 {
+        match level {
+            1..=5 => self.elasticity_levels[level as usize - 1],
+            _ => self.elasticity_levels[0],
+        }
+    }
+}
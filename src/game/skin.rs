@@ -0,0 +1,113 @@
+//! How a participant's balls look: a colour from a fixed palette, and an
+//! optional skin drawn on top.
+
+use serde::{Deserialize, Serialize};
+
+/// Bright, well-separated colours that read against the dark board.
+pub const PALETTE: [[u8; 3]; 16] = [
+    [255, 99, 99],   // Red
+    [80, 200, 255],  // Sky
+    [255, 214, 10],  // Yellow
+    [120, 230, 110], // Green
+    [200, 130, 255], // Violet
+    [255, 160, 60],  // Orange
+    [60, 230, 200],  // Teal
+    [255, 120, 200], // Pink
+    [170, 240, 60],  // Lime
+    [110, 140, 255], // Blue
+    [255, 200, 150], // Peach
+    [230, 230, 230], // White
+    [255, 70, 150],  // Magenta
+    [150, 220, 255], // Ice
+    [210, 180, 90],  // Gold
+    [160, 255, 180], // Mint
+];
+
+/// Drawn over a ball.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Skin {
+    #[default]
+    Plain,
+    Initials,
+    Glyph(String), // An emoji or a couple of letters
+    Image(String), // File name of a PNG/JPEG
+}
+
+impl Skin {
+    /// Read a roster cell: blank, `initials`, an image file name, or a glyph.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let lower = text.to_lowercase();
+        if text.is_empty() {
+            Skin::Plain
+        } else if lower == "initials" {
+            Skin::Initials
+        } else if [".png", ".jpg", ".jpeg"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+        {
+            Skin::Image(text.to_owned())
+        } else {
+            Skin::Glyph(text.to_owned())
+        }
+    }
+
+    /// The roster cell `parse` reads back.
+    pub fn to_text(&self) -> &str {
+        match self {
+            Skin::Plain => "",
+            Skin::Initials => "initials",
+            Skin::Glyph(text) | Skin::Image(text) => text,
+        }
+    }
+}
+
+/// First letters of the first two words of `name`.
+pub fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// A colour for `name` that isn't in `taken`. Each name starts from its own
+/// palette slot, so people keep their colour from draw to draw however the
+/// roster is ordered; past the palette, colours are spread round the hue wheel.
+pub fn pick_color(name: &str, taken: &[[u8; 3]]) -> [u8; 3] {
+    let start = fnv1a(name) as usize;
+    (0..PALETTE.len())
+        .map(|i| PALETTE[(start + i) % PALETTE.len()])
+        .chain((0..).map(extra_color))
+        .find(|c| !taken.contains(c))
+        .unwrap_or(PALETTE[0])
+}
+
+/// Golden-angle hues once the palette runs out.
+fn extra_color(i: u32) -> [u8; 3] {
+    let hue = (i as f32 * 137.508 + 15.0) % 360.0;
+    let value = if i.is_multiple_of(2) { 1.0 } else { 0.8 };
+    hsv_to_rgb(hue, 0.65, value)
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [u8; 3] {
+    let c = value * saturation;
+    let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - c;
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r, g, b].map(|v| ((v + m) * 255.0).round() as u8)
+}
+
+/// Stable across runs and platforms, unlike `DefaultHasher`.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
@@ -0,0 +1,112 @@
+use crate::game::Ball;
+use rapier2d::prelude::*;
+use std::collections::HashMap;
+
+// How long a position change arrow stays visible (seconds).
+pub const TREND_DISPLAY_TIME: f64 = 1.5;
+
+pub struct LiveStanding {
+    pub handle: RigidBodyHandle,
+    pub name: String,
+    pub color: [u8; 3],
+    pub position: usize, // 1-based race position, counting finished balls
+    pub remaining: f32,  // Distance left to the goal
+    pub trend: i32,      // Positive = moved up, negative = moved down
+    pub trend_at: f64,
+}
+
+impl LiveStanding {
+    pub fn show_trend(&self, current_time: f64) -> bool {
+        self.trend != 0 && current_time - self.trend_at < TREND_DISPLAY_TIME
+    }
+}
+
+/// Distance left along `path` for a ball at (x, y).
+/// Falls back to plain height when the map has no usable path.
+pub fn path_remaining(path: &[[f32; 2]], x: f32, y: f32) -> f32 {
+    if path.len() < 2 {
+        return y;
+    }
+
+    // Find the closest point on the polyline
+    let mut best_dist_sq = f32::MAX;
+    let mut best_remaining = 0.0;
+    for i in 0..path.len() - 1 {
+        let [ax, ay] = path[i];
+        let [bx, by] = path[i + 1];
+        let (dx, dy) = (bx - ax, by - ay);
+        let seg_len_sq = dx * dx + dy * dy;
+        let t = if seg_len_sq > 0.0 {
+            (((x - ax) * dx + (y - ay) * dy) / seg_len_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (px, py) = (ax + dx * t, ay + dy * t);
+        let dist_sq = (x - px).powi(2) + (y - py).powi(2);
+        if dist_sq < best_dist_sq {
+            best_dist_sq = dist_sq;
+            // Rest of this segment + every segment after it
+            let mut remaining = seg_len_sq.sqrt() * (1.0 - t);
+            for j in i + 1..path.len() - 1 {
+                let [cx, cy] = path[j];
+                let [ex, ey] = path[j + 1];
+                remaining += ((ex - cx).powi(2) + (ey - cy).powi(2)).sqrt();
+            }
+            best_remaining = remaining;
+        }
+    }
+
+    // Off-path balls still have to get back to the path first
+    best_remaining + best_dist_sq.sqrt()
+}
+
+/// Re-rank active balls by progress, carrying over position change arrows
+/// from the previous frame's standings. `finished_count` balls are already
+/// through the goal and occupy the top positions.
+pub fn compute(
+    balls: &[Ball],
+    rigid_body_set: &RigidBodySet,
+    path: &[[f32; 2]],
+    finished_count: usize,
+    previous: &[LiveStanding],
+    current_time: f64,
+) -> Vec<LiveStanding> {
+    let mut standings: Vec<LiveStanding> = balls
+        .iter()
+        .filter_map(|ball| {
+            let rb = rigid_body_set.get(ball.handle)?;
+            let pos = rb.translation();
+            Some(LiveStanding {
+                handle: ball.handle,
+                name: ball.name.clone(),
+                color: ball.color,
+                position: 0,
+                remaining: path_remaining(path, pos.x, pos.y),
+                trend: 0,
+                trend_at: 0.0,
+            })
+        })
+        .collect();
+
+    standings.sort_by(|a, b| a.remaining.total_cmp(&b.remaining));
+
+    let previous: HashMap<RigidBodyHandle, &LiveStanding> =
+        previous.iter().map(|p| (p.handle, p)).collect();
+    for (i, standing) in standings.iter_mut().enumerate() {
+        standing.position = finished_count + i + 1;
+
+        let Some(prev) = previous.get(&standing.handle) else {
+            continue;
+        };
+
+        if prev.position != standing.position {
+            standing.trend = prev.position as i32 - standing.position as i32;
+            standing.trend_at = current_time;
+        } else {
+            standing.trend = prev.trend;
+            standing.trend_at = prev.trend_at;
+        }
+    }
+
+    standings
+}
@@ -0,0 +1,221 @@
+//! Completed draws, kept between sessions, and per-person statistics.
+
+use crate::files::csv_cell;
+use crate::game::{GameState, Outcome};
+use crate::roster::Entry;
+use serde::{Deserialize, Serialize};
+
+/// One completed draw.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Draw {
+    pub timestamp: u64, // Unix seconds
+    pub roster: String,
+    pub map: String,
+    pub seed: u64,
+    pub condition: String,
+    pub order: Vec<Placing>, // Best first, one per person
+    #[serde(default)]
+    pub entrants: Vec<Entry>, // Who raced, with the tickets they raced with (empty in older draws)
+}
+
+/// One person's (or, in a results export, one ball's) result.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Placing {
+    pub name: String,
+    pub ball: String, // The ball that placed them
+    pub eliminated: bool,
+    #[serde(default)]
+    pub survived: bool, // Still on the board when the race ended
+    pub time: f64, // Race time they left the board
+}
+
+impl Placing {
+    /// Finished ball `i` of the race in `state`.
+    pub fn from_ball(state: &GameState, i: usize) -> Self {
+        let ball = &state.finished_balls[i];
+        Self {
+            name: state.owners[ball.owner as usize].clone(),
+            ball: ball.name.clone(),
+            eliminated: ball.outcome == Outcome::Eliminated,
+            survived: ball.outcome == Outcome::Survived,
+            time: ball.crossed_at,
+        }
+    }
+
+    pub fn outcome(&self) -> &'static str {
+        match (self.eliminated, self.survived) {
+            (true, _) => "eliminated",
+            (_, true) => "survived",
+            _ => "finished",
+        }
+    }
+}
+
+/// Totals for one person across the history.
+#[derive(Clone, Debug)]
+pub struct PersonStats {
+    pub name: String,
+    pub draws: u32,
+    pub wins: u32,
+    pub average_rank: f32,
+    pub last_win: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub draws: Vec<Draw>, // Oldest first
+}
+
+impl Draw {
+    /// The finished race in `state`, placed per `GameState::ranking`, run
+    /// with `entrants`.
+    pub fn from_race(state: &GameState, roster: &str, entrants: Vec<Entry>) -> Self {
+        let order = state
+            .ranking()
+            .into_iter()
+            .map(|i| Placing::from_ball(state, i))
+            .collect();
+        Self {
+            timestamp: now(),
+            roster: roster.to_owned(),
+            map: state.map.name.clone(),
+            seed: state.seed,
+            condition: state.winning_condition.name().to_owned(),
+            order,
+            entrants,
+        }
+    }
+
+    pub fn winner(&self) -> Option<&str> {
+        self.order.first().map(|p| p.name.as_str())
+    }
+
+    /// Tickets `name` raced with, if recorded.
+    pub fn tickets_of(&self, name: &str) -> Option<u32> {
+        self.entrants
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.tickets)
+    }
+
+    /// 1-based place of `name`, if they were in this draw.
+    pub fn rank_of(&self, name: &str) -> Option<usize> {
+        self.order
+            .iter()
+            .position(|p| p.name == name)
+            .map(|i| i + 1)
+    }
+
+    /// Whether `filter` (lowercase) matches the roster, map or anyone in it.
+    pub fn matches(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.roster.to_lowercase().contains(filter)
+            || self.map.to_lowercase().contains(filter)
+            || self
+                .order
+                .iter()
+                .any(|p| p.name.to_lowercase().contains(filter))
+    }
+}
+
+impl History {
+    /// Wins, average place and last win per person, most wins first.
+    pub fn stats(&self) -> Vec<PersonStats> {
+        let mut stats: Vec<PersonStats> = Vec::new();
+        let mut rank_sums: Vec<usize> = Vec::new();
+        for draw in &self.draws {
+            for (i, placing) in draw.order.iter().enumerate() {
+                let index = match stats.iter().position(|s| s.name == placing.name) {
+                    Some(index) => index,
+                    None => {
+                        stats.push(PersonStats {
+                            name: placing.name.clone(),
+                            draws: 0,
+                            wins: 0,
+                            average_rank: 0.0,
+                            last_win: None,
+                        });
+                        rank_sums.push(0);
+                        stats.len() - 1
+                    }
+                };
+                let person = &mut stats[index];
+                person.draws += 1;
+                rank_sums[index] += i + 1;
+                if i == 0 {
+                    person.wins += 1;
+                    person.last_win = Some(draw.timestamp);
+                }
+            }
+        }
+        for (person, sum) in stats.iter_mut().zip(rank_sums) {
+            person.average_rank = sum as f32 / person.draws as f32;
+        }
+        stats.sort_by(|a, b| {
+            b.wins
+                .cmp(&a.wins)
+                .then(a.average_rank.total_cmp(&b.average_rank))
+        });
+        stats
+    }
+
+    /// One line per placing, oldest draw first.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("date,roster,map,seed,condition,rank,name,tickets,ball,eliminated,time\n");
+        for draw in &self.draws {
+            for (i, placing) in draw.order.iter().enumerate() {
+                let cells = [
+                    format_date(draw.timestamp),
+                    csv_cell(&draw.roster),
+                    csv_cell(&draw.map),
+                    draw.seed.to_string(),
+                    csv_cell(&draw.condition),
+                    (i + 1).to_string(),
+                    csv_cell(&placing.name),
+                    draw.tickets_of(&placing.name)
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    csv_cell(&placing.ball),
+                    placing.eliminated.to_string(),
+                    format!("{:.3}", placing.time),
+                ];
+                csv.push_str(&cells.join(","));
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+}
+
+/// Current time in Unix seconds.
+pub fn now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// `YYYY-MM-DD HH:MM` (UTC) for Unix seconds.
+pub fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let minutes = timestamp % 86_400 / 60;
+    // Civil-from-days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
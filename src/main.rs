@@ -1,5 +1,16 @@
 mod app;
+mod audit;
+mod certificate;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod export;
+mod fairness;
+mod files;
 mod game;
+mod history;
+mod proof;
+mod roster;
+mod tournament;
 mod ui;
 
 #[cfg(target_arch = "wasm32")]
@@ -8,6 +19,16 @@ use wasm_bindgen::prelude::*;
 fn main() -> eframe::Result<()> {
     #[cfg(not(target_arch = "wasm32"))]
     {
+        // Any arguments: a headless draw from the command line
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if !args.is_empty() {
+            if let Err(e) = cli::run(&args) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
         // Log to stdout (if you run with `RUST_LOG=debug`)
         // env_logger::init(); // we didn't add env_logger, skipping for now
 
@@ -0,0 +1,302 @@
+//! Commit-reveal for prize draws. Before a race everyone can see a hash of
+//! its seed, entrants, map and rules; afterwards the seed is revealed, and
+//! anyone can run the same race headlessly and check both the hash and the
+//! result.
+
+use crate::game::map_file::MapFile;
+use crate::game::{CloneRank, GameState, WinningCondition};
+use crate::roster::{Entry, Roster};
+
+/// Everything that decides a race.
+#[derive(Clone)]
+pub struct Inputs {
+    pub seed: u64,
+    pub entrants: Vec<Entry>, // Who raced, with the tickets they raced with
+    pub roster: String,       // `roster::to_text` of `entrants`
+    pub map_id: String,       // `MapFile::id`
+    pub condition: WinningCondition,
+    pub clones: CloneRank,
+}
+
+impl Inputs {
+    /// The race with `seed` and `entrants` (people sitting out get 0
+    /// tickets) on `map`, under `state`'s winning rules.
+    pub fn new<'a>(
+        seed: u64,
+        entrants: impl Iterator<Item = (&'a Entry, u32)>,
+        map: &MapFile,
+        state: &GameState,
+    ) -> Self {
+        let entries: Vec<Entry> = entrants
+            .filter(|&(_, tickets)| tickets > 0)
+            .map(|(entry, tickets)| Entry {
+                tickets,
+                ..entry.clone()
+            })
+            .collect();
+        Self {
+            seed,
+            roster: crate::roster::to_text(&entries),
+            entrants: entries,
+            map_id: map.id(),
+            condition: state.winning_condition,
+            clones: state.clone_rank,
+        }
+    }
+
+    /// SHA-256 of the inputs, in hex. Safe to publish before the race: the
+    /// seed can't be worked back out of it.
+    pub fn commitment(&self) -> String {
+        let text = format!(
+            "pinball_gacha draw v1\nseed {}\ncondition {}\nclones {}\nmap {}\n{}",
+            self.seed,
+            condition_key(self.condition),
+            clones_key(self.clones),
+            self.map_id,
+            self.roster
+        );
+        sha256_hex(&text)
+    }
+
+    /// What to publish once the race is over: enough, with the map file, to
+    /// run it again.
+    pub fn reveal(&self, winner: Option<&str>) -> String {
+        format!(
+            "Commitment: {}\nSeed: {}\nCondition: {}\nSplit balls count: {}\nMap id: {}\n\
+             Winner: {}\nRoster:\n{}",
+            self.commitment(),
+            self.seed,
+            condition_key(self.condition),
+            clones_key(self.clones),
+            self.map_id,
+            winner.unwrap_or("-"),
+            self.roster
+        )
+    }
+}
+
+/// Run the race for `seed` and `roster` (tickets as written) on whatever map
+/// `state` holds, to the end.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))] // Only the CLI replays in one go
+pub fn replay(state: &mut GameState, seed: u64, roster: &Roster) {
+    start_replay(state, seed, roster);
+    state.fast_forward(0.0, crate::game::MAX_RACE_TIME);
+}
+
+/// Drop the balls for `replay`, without running the race yet.
+fn start_replay(state: &mut GameState, seed: u64, roster: &Roster) {
+    state.headless = true;
+    state.reset_game();
+    state.seed_race(seed);
+    crate::roster::spawn_balls(state, roster.entrants().map(|e| (e, e.tickets)));
+    state.is_running = true;
+}
+
+/// A published draw to check, as pasted into the verify screen.
+pub struct Claim<'a> {
+    pub seed: u64,
+    pub roster: &'a str,
+    pub map: MapFile,
+    pub condition: WinningCondition,
+    pub clones: CloneRank,
+    pub commitment: &'a str, // Empty = don't check
+    pub winner: &'a str,     // Empty = don't check
+}
+
+/// What re-running a `Claim` showed.
+pub struct Verdict {
+    pub commitment: String,
+    pub commitment_ok: Option<bool>, // None when no commitment was given
+    pub winner: Option<String>,
+    pub winner_ok: Option<bool>,
+    pub ranking: Vec<String>, // Best first
+    pub finished: bool,       // False if it gave up with balls still on the board
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        self.finished && self.commitment_ok != Some(false) && self.winner_ok != Some(false)
+    }
+}
+
+/// A `Claim` being re-run, a chunk of race at a time, so a window can show
+/// progress instead of freezing.
+pub struct Check {
+    state: GameState,
+    commitment: String, // Of the claim's inputs
+    claimed_commitment: String,
+    claimed_winner: String,
+}
+
+impl Check {
+    /// Set up the re-run of `claim` from scratch.
+    pub fn start(claim: Claim) -> Result<Self, String> {
+        let roster = Roster::parse(claim.roster);
+        if let Some(row) = roster.rows.iter().find(|r| !r.errors.is_empty()) {
+            return Err(format!(
+                "Roster line {}: {}",
+                row.line,
+                row.errors.join("; ")
+            ));
+        }
+        if roster.ticket_total() == 0 {
+            return Err("The roster has no tickets".to_owned());
+        }
+
+        let mut state = GameState::new();
+        state.load_map(claim.map);
+        state.winning_condition = claim.condition;
+        state.clone_rank = claim.clones;
+        let inputs = Inputs::new(
+            claim.seed,
+            roster.entrants().map(|e| (e, e.tickets)),
+            &state.map,
+            &state,
+        );
+        start_replay(&mut state, claim.seed, &roster);
+        state.start_fast_forward();
+        Ok(Self {
+            state,
+            commitment: inputs.commitment(),
+            claimed_commitment: claim.commitment.trim().to_owned(),
+            claimed_winner: claim.winner.trim().to_owned(),
+        })
+    }
+
+    /// Race time run so far, and how many balls are through out of all of them.
+    pub fn progress(&self) -> (f64, usize, usize) {
+        let finished = self.state.finished_balls.len();
+        (
+            self.state.sim_time,
+            finished,
+            finished + self.state.balls.len(),
+        )
+    }
+
+    /// Run the next chunk of the race; the verdict once it's over.
+    pub fn step(&mut self) -> Option<Verdict> {
+        self.state.update(0.0);
+        if self.state.fast_forwarding {
+            return None;
+        }
+
+        let state = &self.state;
+        let owner = |i: usize| state.owners[state.finished_balls[i].owner as usize].clone();
+        let winner = state.winner().map(owner);
+        Some(Verdict {
+            commitment_ok: (!self.claimed_commitment.is_empty()).then(|| {
+                self.claimed_commitment
+                    .eq_ignore_ascii_case(&self.commitment)
+            }),
+            winner_ok: (!self.claimed_winner.is_empty())
+                .then(|| winner.as_deref() == Some(self.claimed_winner.as_str())),
+            commitment: self.commitment.clone(),
+            winner,
+            ranking: state.ranking().into_iter().map(owner).collect(),
+            finished: state.race_finished(),
+        })
+    }
+}
+
+/// SHA-256 of `text`, in hex.
+pub fn sha256_hex(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn condition_key(condition: WinningCondition) -> &'static str {
+    match condition {
+        WinningCondition::First => "first",
+        WinningCondition::Last => "last",
+        WinningCondition::LastStanding => "standing",
+    }
+}
+
+pub fn clones_key(clones: CloneRank) -> &'static str {
+    match clones {
+        CloneRank::Best => "best",
+        CloneRank::Worst => "worst",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map_file::MapElement;
+
+    fn map() -> MapFile {
+        let pins = (0..5)
+            .map(|i| MapElement::Pin {
+                x: -100.0 + i as f32 * 50.0,
+                y: 100.0,
+                level: 1 + i as u8,
+            })
+            .collect();
+        MapFile::new("Pins", 500.0, 800.0, pins)
+    }
+
+    const ROSTER: &str = "Alice*2\nBob\nCara\n";
+
+    fn claim<'a>(commitment: &'a str, winner: &'a str) -> Claim<'a> {
+        Claim {
+            seed: 42,
+            roster: ROSTER,
+            map: map(),
+            condition: WinningCondition::First,
+            clones: CloneRank::Best,
+            commitment,
+            winner,
+        }
+    }
+
+    fn run(check: &mut Check) -> Verdict {
+        loop {
+            if let Some(verdict) = check.step() {
+                return verdict;
+            }
+        }
+    }
+
+    #[test]
+    fn check_matches_replay_and_commitment() {
+        let roster = Roster::parse(ROSTER);
+        let mut state = GameState::new();
+        state.load_map(map());
+        let inputs = Inputs::new(
+            42,
+            roster.entrants().map(|e| (e, e.tickets)),
+            &state.map,
+            &state,
+        );
+        replay(&mut state, 42, &roster);
+        let winner = state
+            .winner()
+            .map(|i| state.owners[state.finished_balls[i].owner as usize].clone())
+            .expect("the race has a winner");
+
+        let commitment = inputs.commitment();
+        let verdict = run(&mut Check::start(claim(&commitment, &winner)).unwrap());
+        assert!(verdict.finished);
+        assert_eq!(verdict.commitment_ok, Some(true));
+        assert_eq!(verdict.winner_ok, Some(true));
+        assert!(verdict.passed());
+    }
+
+    #[test]
+    fn wrong_commitment_fails() {
+        let verdict = run(&mut Check::start(claim(&"0".repeat(64), "")).unwrap());
+        assert_eq!(verdict.commitment_ok, Some(false));
+        assert_eq!(verdict.winner_ok, None);
+        assert!(!verdict.passed());
+    }
+
+    #[test]
+    fn bad_roster_is_refused() {
+        let mut bad = claim("", "");
+        bad.roster = "Alice*0\n";
+        assert!(Check::start(bad).is_err());
+    }
+}
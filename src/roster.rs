@@ -0,0 +1,467 @@
+//! Participant lists: `Name*Count` lines, CSV/TSV files and columns pasted
+//! from a spreadsheet all parse into the same `Roster`.
+//!
+//! Columns are name, tickets, color, team, excluded and skin. A header row may
+//! reorder or omit them; without one they are read in that order.
+//!
+//! `Rosters` are the named lists kept between sessions.
+
+use crate::fairness::Fairness;
+use crate::files::csv_cell;
+use crate::game::skin::Skin;
+use crate::game::GameState;
+use serde::{Deserialize, Serialize};
+
+/// One participant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub tickets: u32,
+    pub color: Option<[u8; 3]>, // Picked at random when unset
+    pub team: Option<String>,
+    pub excluded: bool, // Listed but sitting this draw out
+    #[serde(default)]
+    pub skin: Skin,
+}
+
+impl Entry {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            tickets: 1,
+            color: None,
+            team: None,
+            excluded: false,
+            skin: Skin::Plain,
+        }
+    }
+}
+
+/// A named list of people, e.g. "Team Standup".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedRoster {
+    pub name: String,
+    pub entries: Vec<Entry>,
+    #[serde(default)]
+    pub fairness: Fairness,
+}
+
+/// Every saved roster and which one is in use.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rosters {
+    pub saved: Vec<SavedRoster>, // Never empty
+    pub active: usize,
+}
+
+impl Default for Rosters {
+    fn default() -> Self {
+        let mut alice = Entry::new("Alice");
+        alice.tickets = 5;
+        let mut bob = Entry::new("Bob");
+        bob.tickets = 3;
+        Self {
+            saved: vec![SavedRoster {
+                name: "My Roster".to_owned(),
+                entries: vec![alice, bob],
+                fairness: Fairness::default(),
+            }],
+            active: 0,
+        }
+    }
+}
+
+impl Rosters {
+    pub fn active(&self) -> &SavedRoster {
+        &self.saved[self.active.min(self.saved.len() - 1)]
+    }
+
+    pub fn active_mut(&mut self) -> &mut SavedRoster {
+        let i = self.active.min(self.saved.len() - 1);
+        &mut self.saved[i]
+    }
+
+    /// Add an empty roster and switch to it.
+    pub fn add(&mut self, name: String) {
+        self.saved.push(SavedRoster {
+            name,
+            entries: Vec::new(),
+            fairness: Fairness::default(),
+        });
+        self.active = self.saved.len() - 1;
+    }
+
+    /// Delete the active roster (keeping at least one).
+    pub fn remove_active(&mut self) {
+        if self.saved.len() > 1 {
+            self.saved.remove(self.active.min(self.saved.len() - 1));
+            self.active = self.active.saturating_sub(1);
+        }
+    }
+}
+
+/// A parsed line and whatever is wrong with it.
+#[derive(Clone, Debug)]
+pub struct Row {
+    pub line: usize, // 1-based, for error messages
+    pub entry: Entry,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Roster {
+    pub rows: Vec<Row>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Name,
+    Tickets,
+    Color,
+    Team,
+    Excluded,
+    Skin,
+    Ignored,
+}
+
+const COLUMNS: [Column; 6] = [
+    Column::Name,
+    Column::Tickets,
+    Column::Color,
+    Column::Team,
+    Column::Excluded,
+    Column::Skin,
+];
+
+impl Column {
+    fn from_header(cell: &str) -> Option<Self> {
+        match cell.trim().to_lowercase().as_str() {
+            "name" | "names" | "participant" | "person" => Some(Column::Name),
+            "tickets" | "ticket" | "count" | "entries" | "weight" => Some(Column::Tickets),
+            "color" | "colour" => Some(Column::Color),
+            "team" | "group" => Some(Column::Team),
+            "excluded" | "exclude" | "skip" | "absent" => Some(Column::Excluded),
+            "skin" | "icon" | "emoji" | "avatar" => Some(Column::Skin),
+            _ => None,
+        }
+    }
+}
+
+impl Roster {
+    /// Parse `text`, recording problems per row rather than giving up.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim_start_matches('\u{feff}');
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+
+        let delimiter = ['\t', ',', ';']
+            .into_iter()
+            .find(|&d| is_delimited(&lines, d));
+
+        let mut rows = Vec::new();
+        let mut columns = COLUMNS.to_vec();
+        for (i, &(line, text)) in lines.iter().enumerate() {
+            let cells = match delimiter {
+                Some(d) => split_cells(text, d),
+                None => legacy_cells(text),
+            };
+            if i == 0 {
+                let header: Vec<Option<Column>> =
+                    cells.iter().map(|c| Column::from_header(c)).collect();
+                if header.contains(&Some(Column::Name)) {
+                    // Unknown headings are skipped
+                    columns = header
+                        .into_iter()
+                        .map(|c| c.unwrap_or(Column::Ignored))
+                        .collect();
+                    continue;
+                }
+            }
+            rows.push(parse_row(line, &cells, &columns));
+        }
+
+        // Duplicates are flagged on the later row
+        for i in 0..rows.len() {
+            let key = rows[i].entry.name.to_lowercase();
+            if key.is_empty() {
+                continue;
+            }
+            if let Some(first) = rows[..i]
+                .iter()
+                .find(|r| r.entry.name.to_lowercase() == key)
+            {
+                let message = format!("Duplicate of line {}", first.line);
+                rows[i].errors.push(message);
+            }
+        }
+        Self { rows }
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.rows.iter().filter(|r| !r.errors.is_empty()).count()
+    }
+
+    /// Participants taking part in the draw.
+    pub fn entrants(&self) -> impl Iterator<Item = &Entry> {
+        self.rows.iter().map(|r| &r.entry).filter(|e| !e.excluded)
+    }
+
+    pub fn ticket_total(&self) -> u32 {
+        self.entrants().map(|e| e.tickets).sum()
+    }
+
+    /// Every row's entry, if there were no errors.
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        if self.error_count() > 0 {
+            return None;
+        }
+        Some(self.rows.iter().map(|r| r.entry.clone()).collect())
+    }
+}
+
+/// Drop each entrant's balls: one per ticket, named `Name#i` when they
+/// have more than one.
+pub fn spawn_balls<'a>(state: &mut GameState, entrants: impl Iterator<Item = (&'a Entry, u32)>) {
+    for (entry, tickets) in entrants {
+        if tickets == 1 {
+            let name = entry.name.clone();
+            state.spawn_ball(&entry.name, name, entry.color, entry.skin.clone());
+            continue;
+        }
+        for i in 1..=tickets {
+            let name = format!("{}#{}", entry.name, i);
+            state.spawn_ball(&entry.name, name, entry.color, entry.skin.clone());
+        }
+    }
+}
+
+/// Write `entries` back out as text `Roster::parse` reads: `Name*Count`
+/// lines when that loses nothing, CSV with a header otherwise.
+pub fn to_text(entries: &[Entry]) -> String {
+    let plain = entries.iter().all(|e| {
+        e.color.is_none()
+            && e.team.is_none()
+            && !e.excluded
+            && e.skin == Skin::Plain
+            && !e.name.contains(['*', ',', ';', '\t', '"'])
+    });
+    let mut text = String::new();
+    if plain {
+        for e in entries {
+            match e.tickets {
+                1 => text.push_str(&e.name),
+                n => text.push_str(&format!("{}*{}", e.name, n)),
+            }
+            text.push('\n');
+        }
+        return text;
+    }
+    text.push_str("name,tickets,color,team,excluded,skin\n");
+    for e in entries {
+        let color = e
+            .color
+            .map(|[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b))
+            .unwrap_or_default();
+        let cells = [
+            csv_cell(&e.name),
+            e.tickets.to_string(),
+            color,
+            csv_cell(e.team.as_deref().unwrap_or("")),
+            if e.excluded { "yes" } else { "" }.to_owned(),
+            csv_cell(e.skin.to_text()),
+        ];
+        text.push_str(&cells.join(","));
+        text.push('\n');
+    }
+    text
+}
+
+fn parse_row(line: usize, cells: &[String], columns: &[Column]) -> Row {
+    let mut entry = Entry::new("");
+    let mut errors = Vec::new();
+    for (cell, &column) in cells.iter().zip(columns) {
+        let cell = cell.trim();
+        match column {
+            Column::Name => entry.name = cell.to_owned(),
+            Column::Tickets if cell.is_empty() => {}
+            Column::Tickets => match cell.parse::<u32>() {
+                Ok(0) => errors.push("Tickets must be at least 1".to_owned()),
+                Ok(n) => entry.tickets = n,
+                Err(_) => errors.push(format!("Tickets \"{}\" is not a number", cell)),
+            },
+            Column::Color if cell.is_empty() => {}
+            Column::Color => match parse_color(cell) {
+                Some(color) => entry.color = Some(color),
+                None => errors.push(format!("Color \"{}\" is not #RRGGBB", cell)),
+            },
+            Column::Team => {
+                if !cell.is_empty() {
+                    entry.team = Some(cell.to_owned());
+                }
+            }
+            Column::Skin => entry.skin = Skin::parse(cell),
+            Column::Ignored => {}
+            Column::Excluded => match cell.to_lowercase().as_str() {
+                "" | "no" | "n" | "false" | "0" => {}
+                "yes" | "y" | "true" | "1" | "x" => entry.excluded = true,
+                _ => errors.push(format!("Excluded \"{}\" is not yes/no", cell)),
+            },
+        }
+    }
+    if entry.name.is_empty() {
+        errors.insert(0, "Name is empty".to_owned());
+    }
+    Row {
+        line,
+        entry,
+        errors,
+    }
+}
+
+/// Split one CSV/TSV line. Quoted cells may contain the delimiter and `""`.
+fn split_cells(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                quoted = true;
+            }
+            c if c == delimiter && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+/// Whether `lines` are cells split by `delimiter`: they start with a header
+/// row, or every line has the same number of cells. A stray comma in a
+/// `Name*Count` list ("Smith, John*3") is part of the name.
+fn is_delimited(lines: &[(usize, &str)], delimiter: char) -> bool {
+    let Some((_, first)) = lines.first() else {
+        return false;
+    };
+    let first = split_cells(first, delimiter);
+    if first.len() > 1
+        && first
+            .iter()
+            .any(|c| Column::from_header(c) == Some(Column::Name))
+    {
+        return true;
+    }
+    lines.len() > 1
+        && first.len() > 1
+        && lines[1..]
+            .iter()
+            .all(|(_, line)| split_cells(line, delimiter).len() == first.len())
+}
+
+/// The original `Name*Count` format (count optional).
+fn legacy_cells(line: &str) -> Vec<String> {
+    match line.rsplit_once('*') {
+        Some((name, count)) => vec![name.to_owned(), count.to_owned()],
+        None => vec![line.to_owned()],
+    }
+}
+
+/// `#RRGGBB`, `RRGGBB` or `#RGB`.
+pub fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                rgb[i] = channel(&c.to_string())? * 17;
+            }
+            Some(rgb)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(roster: &Roster) -> Vec<(&str, u32)> {
+        roster
+            .rows
+            .iter()
+            .map(|r| (r.entry.name.as_str(), r.entry.tickets))
+            .collect()
+    }
+
+    #[test]
+    fn name_count_lines() {
+        let roster = Roster::parse("Alice*3\nBob\n\nCara*2\n");
+        assert_eq!(names(&roster), [("Alice", 3), ("Bob", 1), ("Cara", 2)]);
+        assert_eq!(roster.ticket_total(), 6);
+    }
+
+    #[test]
+    fn stray_comma_stays_in_the_name() {
+        let roster = Roster::parse("Smith, John*3\nAlice\nBob*2");
+        assert_eq!(
+            names(&roster),
+            [("Smith, John", 3), ("Alice", 1), ("Bob", 2)]
+        );
+        assert_eq!(roster.error_count(), 0);
+    }
+
+    #[test]
+    fn csv_header_picks_columns() {
+        let roster = Roster::parse("team,Name,notes,Tickets\nRed,Alice,hi,2\nBlue,Bob,,\n");
+        assert_eq!(names(&roster), [("Alice", 2), ("Bob", 1)]);
+        assert_eq!(roster.rows[0].entry.team.as_deref(), Some("Red"));
+        assert_eq!(roster.rows[0].line, 2);
+    }
+
+    #[test]
+    fn headerless_cells_with_matching_counts() {
+        let roster = Roster::parse("Alice\t2\t#ff0000\nBob\t1\t\n");
+        assert_eq!(names(&roster), [("Alice", 2), ("Bob", 1)]);
+        assert_eq!(roster.rows[0].entry.color, Some([255, 0, 0]));
+
+        let roster = Roster::parse("\"Smith, John\",3\nAlice,1\n");
+        assert_eq!(names(&roster), [("Smith, John", 3), ("Alice", 1)]);
+    }
+
+    #[test]
+    fn errors_are_kept_per_row() {
+        let roster = Roster::parse("name,tickets\nAlice,0\nBob,lots\nalice,1\n");
+        assert_eq!(roster.error_count(), 3);
+        assert_eq!(roster.rows[2].errors, ["Duplicate of line 2"]);
+        assert!(roster.entries().is_none());
+    }
+
+    #[test]
+    fn to_text_round_trips() {
+        let mut alice = Entry::new("Smith, Alice");
+        alice.tickets = 4;
+        alice.excluded = true;
+        let entries = vec![alice, Entry::new("Bob")];
+        let roster = Roster::parse(&to_text(&entries));
+        assert_eq!(roster.entries(), Some(entries));
+    }
+}
@@ -0,0 +1,448 @@
+//! Tournaments of several races: best-of-N series scored by points per
+//! place, heats whose top finishers meet in a final, and knockout brackets
+//! that keep racing heats until the field fits in one final.
+//!
+//! Each round has its own map (the same one throughout, or a fresh random
+//! one per round). The tournament is saved after every race, so it can be
+//! picked up again after closing the app.
+
+use crate::game::map_file::MapFile;
+use crate::roster::Entry;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Kind {
+    Series,  // Everyone in every race; most points wins
+    Heats,   // One round of heats, then a final
+    Bracket, // Rounds of heats until one final is left
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Series => "Best-of-N Series",
+            Kind::Heats => "Heats and Final",
+            Kind::Bracket => "Knockout Bracket",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MapRotation {
+    Same,   // The board's map at the start, every round
+    Rotate, // A new random map each round
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub kind: Kind,
+    pub races: u32,       // Series length
+    pub points: Vec<u32>, // Series points for 1st, 2nd, ...; later places get none
+    pub heat_size: usize, // People per heat
+    pub advance: usize,   // Top finishers of each heat who go through
+    pub maps: MapRotation,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            kind: Kind::Series,
+            races: 3,
+            points: vec![10, 8, 6, 5, 4, 3, 2, 1],
+            heat_size: 4,
+            advance: 2,
+            maps: MapRotation::Same,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Race {
+    pub names: Vec<String>,  // Who's in it
+    pub result: Vec<String>, // One per person, best first; empty until run
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Round {
+    pub name: String, // "Race 2", "Round 1", "Final"...
+    pub map: MapFile,
+    pub races: Vec<Race>,
+}
+
+impl Round {
+    pub fn is_done(&self) -> bool {
+        self.races.iter().all(|r| !r.result.is_empty())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tournament {
+    pub roster: String,
+    pub started: u64, // Unix seconds
+    pub settings: Settings,
+    pub entrants: Vec<Entry>, // With the tickets they race with, fixed at the start
+    pub rounds: Vec<Round>,   // Added one at a time as the previous one finishes
+}
+
+/// One person's line in the overall table.
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub name: String,
+    pub points: u32,          // Ranks a series; breaks ties in a knockout
+    pub wins: u32,            // Races won
+    pub reached: usize,       // Index of the last round they raced in
+    pub place: Option<usize>, // Their place in that round's race, once run
+    pub out: bool,            // Knocked out
+}
+
+impl Tournament {
+    /// Set up the first round for `entrants` (people with 0 tickets left
+    /// out) on `map`.
+    pub fn new(
+        roster: &str,
+        settings: Settings,
+        entrants: Vec<Entry>,
+        map: MapFile,
+    ) -> Result<Self, String> {
+        let entrants: Vec<Entry> = entrants.into_iter().filter(|e| e.tickets > 0).collect();
+        if entrants.len() < 2 {
+            return Err("A tournament needs at least two people with tickets".to_owned());
+        }
+        if settings.kind != Kind::Series && settings.advance >= settings.heat_size {
+            return Err("Fewer people must go through than race in each heat".to_owned());
+        }
+        let names = entrants.iter().map(|e| e.name.clone()).collect();
+        let mut tournament = Self {
+            roster: roster.to_owned(),
+            started: crate::history::now(),
+            settings,
+            entrants,
+            rounds: Vec::new(),
+        };
+        tournament.add_round(names, map);
+        Ok(tournament)
+    }
+
+    /// `(round, race)` of the first race not run yet.
+    pub fn next_race(&self) -> Option<(usize, usize)> {
+        self.rounds.iter().enumerate().find_map(|(r, round)| {
+            round
+                .races
+                .iter()
+                .position(|race| race.result.is_empty())
+                .map(|i| (r, i))
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_race().is_none()
+    }
+
+    /// "Round 1, heat 2 of 3" for the race at `(round, race)`.
+    pub fn race_label(&self, round: usize, race: usize) -> String {
+        let r = &self.rounds[round];
+        match r.races.len() {
+            1 => r.name.clone(),
+            n => format!("{}, heat {} of {}", r.name, race + 1, n),
+        }
+    }
+
+    /// Entries for the people in a race, in roster order.
+    pub fn entries(&self, round: usize, race: usize) -> Vec<Entry> {
+        let names = &self.rounds[round].races[race].names;
+        self.entrants
+            .iter()
+            .filter(|e| names.contains(&e.name))
+            .cloned()
+            .collect()
+    }
+
+    /// Store a race's result, and set up the next round once every race in
+    /// this one has run.
+    pub fn record(&mut self, round: usize, race: usize, result: Vec<String>, seed: u64) {
+        let r = &mut self.rounds[round].races[race];
+        r.result = result;
+        r.seed = Some(seed);
+        if round + 1 == self.rounds.len() && self.rounds[round].is_done() {
+            self.next_round();
+        }
+    }
+
+    fn next_round(&mut self) {
+        let last = self.rounds.last().expect("a tournament has rounds");
+        if last.name == "Final" {
+            return;
+        }
+        let names: Vec<String> = match self.settings.kind {
+            Kind::Series => {
+                if self.rounds.len() >= self.settings.races as usize {
+                    return;
+                }
+                self.entrants.iter().map(|e| e.name.clone()).collect()
+            }
+            Kind::Heats | Kind::Bracket => last
+                .races
+                .iter()
+                .flat_map(|race| race.result.iter().take(self.settings.advance).cloned())
+                .collect(),
+        };
+        let map = match self.settings.maps {
+            MapRotation::Same => last.map.clone(),
+            MapRotation::Rotate => random_map(&last.map, self.rounds.len() + 1),
+        };
+        self.add_round(names, map);
+    }
+
+    /// Race `names` next: all of them for a series, a final if they fit in
+    /// one heat (or the heats stopped thinning the field), heats otherwise.
+    fn add_round(&mut self, mut names: Vec<String>, map: MapFile) {
+        let number = self.rounds.len() + 1;
+        let race = |names: Vec<String>| Race {
+            names,
+            result: Vec::new(),
+            seed: None,
+        };
+        if self.settings.kind == Kind::Series {
+            self.rounds.push(Round {
+                name: format!("Race {}", number),
+                map,
+                races: vec![race(names)],
+            });
+            return;
+        }
+
+        let field_before = self
+            .rounds
+            .last()
+            .map(|r| r.races.iter().map(|race| race.names.len()).sum::<usize>());
+        let final_now = names.len() <= self.settings.heat_size
+            || field_before.is_some_and(|n| names.len() >= n)
+            || self.settings.kind == Kind::Heats && !self.rounds.is_empty();
+        if final_now {
+            self.rounds.push(Round {
+                name: "Final".to_owned(),
+                map,
+                races: vec![race(names)],
+            });
+            return;
+        }
+
+        // Evenly sized heats, drawn at random
+        names.shuffle(&mut rand::thread_rng());
+        let heats = names.len().div_ceil(self.settings.heat_size);
+        let mut races: Vec<Vec<String>> = vec![Vec::new(); heats];
+        for (i, name) in names.into_iter().enumerate() {
+            races[i % heats].push(name);
+        }
+        self.rounds.push(Round {
+            name: match self.settings.kind {
+                Kind::Heats => "Heats".to_owned(),
+                _ => format!("Round {}", number),
+            },
+            map,
+            races: races.into_iter().map(race).collect(),
+        });
+    }
+
+    /// Everyone, best first: by points in a series; by how far they got,
+    /// then their place in their last race, in a knockout.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .map(|e| Standing {
+                name: e.name.clone(),
+                points: 0,
+                wins: 0,
+                reached: 0,
+                place: None,
+                out: false,
+            })
+            .collect();
+        for (r, round) in self.rounds.iter().enumerate() {
+            for race in &round.races {
+                for name in &race.names {
+                    let Some(s) = standings.iter_mut().find(|s| &s.name == name) else {
+                        continue;
+                    };
+                    s.reached = r;
+                    s.place = race.result.iter().position(|n| n == name).map(|i| i + 1);
+                    if let Some(place) = s.place {
+                        s.points += self.settings.points.get(place - 1).copied().unwrap_or(0);
+                        s.wins += u32::from(place == 1);
+                    }
+                }
+            }
+        }
+
+        if self.settings.kind == Kind::Series {
+            standings.sort_by(|a, b| b.points.cmp(&a.points).then(b.wins.cmp(&a.wins)));
+            return standings;
+        }
+        let current = self.rounds.len() - 1;
+        for s in &mut standings {
+            s.out = s.reached < current;
+        }
+        standings.sort_by(|a, b| {
+            b.reached
+                .cmp(&a.reached)
+                .then(
+                    a.place
+                        .unwrap_or(usize::MAX)
+                        .cmp(&b.place.unwrap_or(usize::MAX)),
+                )
+                .then(b.points.cmp(&a.points))
+        });
+        standings
+    }
+
+    /// The overall winner, once the last race has run.
+    pub fn champion(&self) -> Option<String> {
+        if !self.is_finished() {
+            return None;
+        }
+        self.standings().first().map(|s| s.name.clone())
+    }
+}
+
+/// A fresh random layout for round `number`, the size of `map` and keeping
+/// its physics settings (as "New Map" does).
+fn random_map(map: &MapFile, number: usize) -> MapFile {
+    let mut random = MapFile::new(
+        &format!("Random Map {}", number),
+        map.width,
+        map.height,
+        crate::game::maps::generate_elements(map.width, map.height),
+    );
+    random.physics = map.physics.clone();
+    random
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(settings: Settings, names: &[&str]) -> Result<Tournament, String> {
+        let entrants = names.iter().map(|name| Entry::new(name)).collect();
+        Tournament::new(
+            "Team",
+            settings,
+            entrants,
+            MapFile::new("T", 500.0, 800.0, Vec::new()),
+        )
+    }
+
+    fn knockout(kind: Kind, heat_size: usize, advance: usize) -> Settings {
+        Settings {
+            kind,
+            heat_size,
+            advance,
+            ..Settings::default()
+        }
+    }
+
+    /// Run every race left, each finishing in the order its names are listed.
+    fn run_all(tournament: &mut Tournament) {
+        while let Some((round, race)) = tournament.next_race() {
+            let result = tournament.rounds[round].races[race].names.clone();
+            tournament.record(round, race, result, 0);
+        }
+    }
+
+    fn field(round: &Round) -> usize {
+        round.races.iter().map(|r| r.names.len()).sum()
+    }
+
+    #[test]
+    fn rejects_bad_setups() {
+        let mut nobody = Entry::new("Bob");
+        nobody.tickets = 0;
+        let only_one = Tournament::new(
+            "Team",
+            Settings::default(),
+            vec![Entry::new("Alice"), nobody],
+            MapFile::new("T", 500.0, 800.0, Vec::new()),
+        );
+        assert!(only_one.is_err());
+        assert!(start(knockout(Kind::Heats, 4, 4), &["A", "B", "C", "D", "E"]).is_err());
+        assert!(start(knockout(Kind::Bracket, 3, 5), &["A", "B", "C", "D", "E"]).is_err());
+        // A series has no heats to go through
+        assert!(start(knockout(Kind::Series, 4, 4), &["A", "B"]).is_ok());
+    }
+
+    #[test]
+    fn series_scores_every_race() {
+        let settings = Settings {
+            races: 3,
+            points: vec![5, 3],
+            ..Settings::default()
+        };
+        let mut t = start(settings, &["Alice", "Bob", "Cara"]).unwrap();
+        for winner in ["Bob", "Alice", "Bob"] {
+            assert!(t.champion().is_none());
+            let (round, race) = t.next_race().unwrap();
+            let mut result = vec![winner.to_owned()];
+            result.extend(
+                t.rounds[round].races[race]
+                    .names
+                    .iter()
+                    .filter(|n| *n != winner)
+                    .cloned(),
+            );
+            t.record(round, race, result, 0);
+        }
+        assert_eq!(t.rounds.len(), 3);
+        assert!(t.is_finished());
+        let standings = t.standings();
+        assert_eq!(standings[0].name, "Bob");
+        assert_eq!((standings[0].points, standings[0].wins), (13, 2));
+        assert_eq!(
+            (standings[1].name.as_str(), standings[1].points),
+            ("Alice", 11)
+        );
+        assert_eq!(t.champion().as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn heats_lead_to_one_final() {
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"];
+        let mut t = start(knockout(Kind::Heats, 4, 2), &names).unwrap();
+        assert_eq!(t.rounds[0].name, "Heats");
+        assert_eq!(t.rounds[0].races.len(), 3);
+        run_all(&mut t);
+        // Six go through: more than a heat, but heats only get one round
+        assert_eq!(t.rounds.len(), 2);
+        assert_eq!(t.rounds[1].name, "Final");
+        assert_eq!(field(&t.rounds[1]), 6);
+        let champion = t.champion().unwrap();
+        assert_eq!(t.rounds[1].races[0].result[0], champion);
+    }
+
+    #[test]
+    fn bracket_races_until_the_field_fits() {
+        let names: Vec<String> = (1..=16).map(|i| format!("P{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut t = start(knockout(Kind::Bracket, 4, 2), &names).unwrap();
+        run_all(&mut t);
+        let fields: Vec<usize> = t.rounds.iter().map(field).collect();
+        assert_eq!(fields, [16, 8, 4]);
+        assert_eq!(t.rounds[2].name, "Final");
+        let standings = t.standings();
+        assert_eq!(standings.iter().filter(|s| !s.out).count(), 4);
+        assert_eq!(t.champion(), Some(standings[0].name.clone()));
+    }
+
+    #[test]
+    fn bracket_ends_when_the_field_stops_shrinking() {
+        // Heats of 3 and 2 with 3 going through keep all five
+        let mut t = start(knockout(Kind::Bracket, 4, 3), &["A", "B", "C", "D", "E"]).unwrap();
+        assert_eq!(t.rounds[0].races.len(), 2);
+        run_all(&mut t);
+        assert_eq!(t.rounds.len(), 2);
+        assert_eq!(t.rounds[1].name, "Final");
+        assert_eq!(field(&t.rounds[1]), 5);
+        assert!(t.champion().is_some());
+    }
+}
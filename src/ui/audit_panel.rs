@@ -0,0 +1,99 @@
+use crate::audit::{AuditLog, Break, Event};
+use eframe::egui;
+
+/// The last check of the chain, redone only when the log changes.
+#[derive(Default)]
+pub struct AuditView {
+    checked: Option<(usize, String, Result<(), Break>)>, // Length and head it was run on
+}
+
+impl AuditView {
+    fn verify(&mut self, log: &AuditLog) -> &Result<(), Break> {
+        let stale = !matches!(
+            &self.checked,
+            Some((len, head, _)) if *len == log.records.len() && head == log.head()
+        );
+        if stale {
+            self.checked = Some((log.records.len(), log.head().to_owned(), log.verify()));
+        }
+        &self.checked.as_ref().expect("just checked").2
+    }
+}
+
+/// Sidebar section showing whether the audit log's chain holds, its head
+/// hash and the records, newest first. Returns a status message when the
+/// log was exported.
+pub fn show(ui: &mut egui::Ui, log: &AuditLog, view: &mut AuditView) -> Option<String> {
+    let mut status = None;
+    egui::CollapsingHeader::new(format!("Audit Log ({})", log.records.len()))
+        .id_salt("audit_log")
+        .show(ui, |ui| {
+            match view.verify(log) {
+                Ok(()) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, "✔ Chain intact");
+                }
+                Err(b) => {
+                    ui.colored_label(
+                        egui::Color32::LIGHT_RED,
+                        format!("✖ Record #{} {}", b.index, b.reason),
+                    );
+                }
+            }
+            let head = log.head();
+            ui.horizontal(|ui| {
+                ui.label("Head:");
+                ui.monospace(&head[..16]).on_hover_text(head);
+                if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                    ui.ctx().copy_text(head.to_owned());
+                }
+            });
+            ui.label(
+                egui::RichText::new(
+                    "Note the head down now and then: a log cut short still forms a valid chain.",
+                )
+                .small(),
+            );
+            if ui
+                .add_enabled(!log.records.is_empty(), egui::Button::new("Export JSON"))
+                .clicked()
+            {
+                let result = crate::files::save_text(
+                    &crate::files::stamped_name("audit_log", "json"),
+                    &log.to_json(),
+                    "application/json",
+                );
+                status = Some(result.unwrap_or_else(|e| e));
+            }
+
+            egui::ScrollArea::vertical()
+                .id_salt("audit_log_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (i, record) in log.records.iter().enumerate().rev() {
+                        ui.horizontal(|ui| {
+                            let line = crate::audit::describe(record);
+                            let text = match record.event {
+                                Event::Started => egui::RichText::new(line),
+                                Event::Aborted => {
+                                    egui::RichText::new(line).color(egui::Color32::ORANGE)
+                                }
+                                Event::Finished => egui::RichText::new(line).strong(),
+                            };
+                            ui.label(text).on_hover_text(format!(
+                                "Roster: {}\nCommitment: {}\nHash: {}",
+                                record.roster, record.commitment, record.hash
+                            ));
+                            let aborts = log.aborts_before(i);
+                            if record.event == Event::Started && aborts > 0 {
+                                ui.colored_label(egui::Color32::ORANGE, format!("⚠ {}", aborts))
+                                    .on_hover_text(format!(
+                                        "Started after {} aborted run(s)",
+                                        aborts
+                                    ));
+                            }
+                        });
+                    }
+                });
+        });
+    status
+}
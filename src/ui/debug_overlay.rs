@@ -0,0 +1,192 @@
+use crate::game::GameState;
+use eframe::egui;
+use rapier2d::prelude::*;
+
+const OUTLINE: egui::Color32 = egui::Color32::from_rgb(120, 255, 120);
+const SENSOR: egui::Color32 = egui::Color32::from_rgb(255, 220, 0);
+const JOINT: egui::Color32 = egui::Color32::from_rgb(255, 120, 0);
+const VELOCITY: egui::Color32 = egui::Color32::from_rgb(0, 200, 255);
+const CONTACT: egui::Color32 = egui::Color32::from_rgb(255, 60, 60);
+const SLEEPING: egui::Color32 = egui::Color32::from_rgb(140, 140, 255);
+
+// Velocity vectors are drawn as the distance covered in this many seconds.
+const VELOCITY_SCALE: f32 = 0.1;
+
+/// Physics debug overlay: collider outlines (sensors dashed), joint anchors and
+/// motor directions, ball velocities, contacts and sleeping bodies, plus a
+/// readout from `PhysicsEngine`.
+pub fn paint(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    state: &GameState,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) {
+    let physics = &state.physics;
+
+    // Collider outlines
+    for (_handle, collider) in physics.collider_set.iter() {
+        let sensor = collider.is_sensor();
+        let color = if sensor { SENSOR } else { OUTLINE };
+        let stroke = egui::Stroke::new(1.0, color);
+        let transform = collider.position();
+
+        let outline: Vec<egui::Pos2> = if let Some(ball) = collider.shape().as_ball() {
+            (0..24)
+                .map(|i| {
+                    let a = i as f32 / 24.0 * std::f32::consts::TAU;
+                    let p = transform * point![ball.radius * a.cos(), ball.radius * a.sin()];
+                    to_screen(p.x, p.y)
+                })
+                .collect()
+        } else if let Some(cuboid) = collider.shape().as_cuboid() {
+            let hx = cuboid.half_extents.x;
+            let hy = cuboid.half_extents.y;
+            [
+                point![-hx, -hy],
+                point![hx, -hy],
+                point![hx, hy],
+                point![-hx, hy],
+            ]
+            .iter()
+            .map(|p| {
+                let world_p = transform * p;
+                to_screen(world_p.x, world_p.y)
+            })
+            .collect()
+        } else if let Some(tri) = collider.shape().as_triangle() {
+            [tri.a, tri.b, tri.c]
+                .iter()
+                .map(|p| {
+                    let world_p = transform * p;
+                    to_screen(world_p.x, world_p.y)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        if outline.len() < 2 {
+            continue;
+        }
+        let mut closed = outline.clone();
+        closed.push(outline[0]);
+        if sensor {
+            painter.extend(egui::Shape::dashed_line(&closed, stroke, 4.0, 3.0));
+        } else {
+            painter.add(egui::Shape::line(closed, stroke));
+        }
+    }
+
+    // Joint anchors and motor directions
+    for (_handle, joint) in physics.impulse_joint_set.iter() {
+        let (Some(rb1), Some(rb2)) = (
+            physics.rigid_body_set.get(joint.body1),
+            physics.rigid_body_set.get(joint.body2),
+        ) else {
+            continue;
+        };
+        let a1 = rb1.position() * joint.data.local_anchor1();
+        let a2 = rb2.position() * joint.data.local_anchor2();
+        let p1 = to_screen(a1.x, a1.y);
+        let p2 = to_screen(a2.x, a2.y);
+
+        painter.line_segment([p1, p2], egui::Stroke::new(1.0, JOINT));
+        painter.line_segment(
+            [p1 - egui::vec2(4.0, 0.0), p1 + egui::vec2(4.0, 0.0)],
+            egui::Stroke::new(1.5, JOINT),
+        );
+        painter.line_segment(
+            [p1 - egui::vec2(0.0, 4.0), p1 + egui::vec2(0.0, 4.0)],
+            egui::Stroke::new(1.5, JOINT),
+        );
+
+        // Curved arrow showing which way the motor drives
+        if let Some(motor) = joint.data.motor(JointAxis::AngX) {
+            if motor.target_vel.abs() > f32::EPSILON {
+                let dir = motor.target_vel.signum();
+                let radius = 12.0;
+                let arc: Vec<egui::Pos2> = (0..=12)
+                    .map(|i| {
+                        // World y is up, screen y is down: flip the angle
+                        let a = -dir * (i as f32 / 12.0) * 1.5 * std::f32::consts::PI;
+                        p1 + egui::vec2(radius * a.cos(), radius * a.sin())
+                    })
+                    .collect();
+                let tip = arc[arc.len() - 1];
+                let before = arc[arc.len() - 2];
+                painter.add(egui::Shape::line(arc, egui::Stroke::new(1.5, JOINT)));
+                painter.arrow(
+                    before,
+                    (tip - before).normalized() * 5.0,
+                    egui::Stroke::new(1.5, JOINT),
+                );
+            }
+        }
+    }
+
+    // Velocity vectors and sleeping bodies
+    for (handle, rb) in physics.rigid_body_set.iter() {
+        if !rb.is_dynamic() {
+            continue;
+        }
+        let pos = rb.translation();
+        let screen_pos = to_screen(pos.x, pos.y);
+
+        if rb.is_sleeping() {
+            painter.circle_stroke(screen_pos, 10.0, egui::Stroke::new(1.0, SLEEPING));
+            painter.text(
+                screen_pos + egui::vec2(8.0, -8.0),
+                egui::Align2::LEFT_BOTTOM,
+                "z",
+                egui::FontId::monospace(10.0),
+                SLEEPING,
+            );
+        }
+
+        if state.balls.iter().any(|b| b.handle == handle) {
+            let vel = rb.linvel() * VELOCITY_SCALE;
+            painter.arrow(
+                screen_pos,
+                egui::vec2(vel.x, -vel.y),
+                egui::Stroke::new(1.5, VELOCITY),
+            );
+        }
+    }
+
+    // Active contacts and their normals
+    for pair in physics.narrow_phase.contact_pairs() {
+        if !pair.has_any_active_contact {
+            continue;
+        }
+        for manifold in &pair.manifolds {
+            let normal = manifold.data.normal;
+            for contact in &manifold.data.solver_contacts {
+                let p = to_screen(contact.point.x, contact.point.y);
+                painter.circle_filled(p, 2.5, CONTACT);
+                painter.line_segment(
+                    [p, p + egui::vec2(normal.x, -normal.y) * 12.0],
+                    egui::Stroke::new(1.0, CONTACT),
+                );
+            }
+        }
+    }
+
+    // Readout
+    let readout = format!(
+        "step {:.2} ms\nbodies {}\ncolliders {}\njoints {}\ncontacts {}",
+        physics.step_time_ms,
+        physics.rigid_body_set.len(),
+        physics.collider_set.len(),
+        physics.impulse_joint_set.len(),
+        physics.active_contact_count(),
+    );
+    let font = egui::FontId::monospace(11.0);
+    let galley = painter.layout_no_wrap(readout, font, egui::Color32::WHITE);
+    let pos = rect.right_top() + egui::vec2(-galley.size().x - 10.0, 10.0);
+    painter.rect_filled(
+        egui::Rect::from_min_size(pos, galley.size()).expand(4.0),
+        2.0,
+        egui::Color32::from_black_alpha(180),
+    );
+    painter.galley(pos, galley, egui::Color32::WHITE);
+}
@@ -0,0 +1,71 @@
+use crate::fairness::{Adjusted, Fairness};
+use eframe::egui;
+
+/// Sidebar section for the active roster's `Fairness` rules, and the tickets
+/// each person will actually get in the next draw.
+pub fn show(ui: &mut egui::Ui, rules: &mut Fairness, adjusted: &[Adjusted]) {
+    let title = if rules.is_off() {
+        "Fairness Rules (off)".to_owned()
+    } else {
+        let total: u32 = adjusted.iter().map(|a| a.tickets).sum();
+        format!("Fairness Rules ({} tickets in play)", total)
+    };
+    egui::CollapsingHeader::new(title)
+        .id_salt("fairness_rules")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Sit out after winning in the last");
+                ui.add(egui::DragValue::new(&mut rules.exclude_recent).range(0..=20));
+                ui.label("draws");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Lose");
+                ui.add(egui::DragValue::new(&mut rules.win_penalty).range(0..=20));
+                ui.label("tickets per win in the last");
+                ui.add(egui::DragValue::new(&mut rules.penalty_draws).range(1..=50));
+                ui.label("draws");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Gain");
+                ui.add(egui::DragValue::new(&mut rules.drought_bonus).range(0..=20));
+                ui.label("per draw after");
+                ui.add(egui::DragValue::new(&mut rules.drought_draws).range(0..=50));
+                ui.label("without a win, up to");
+                ui.add(egui::DragValue::new(&mut rules.max_bonus).range(1..=100));
+            });
+            ui.label(egui::RichText::new("Set a number to 0 to turn its rule off.").small());
+
+            ui.separator();
+            ui.label("Tickets for the next draw:");
+            egui::ScrollArea::vertical()
+                .id_salt("fairness_scroll")
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("fairness_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("Base");
+                            ui.strong("Draw");
+                            ui.strong("");
+                            ui.end_row();
+                            for person in adjusted {
+                                let mut name = egui::RichText::new(&person.name);
+                                if person.tickets == 0 {
+                                    name = name.strikethrough().weak();
+                                }
+                                ui.label(name);
+                                ui.label(person.base.to_string());
+                                let color = match person.tickets.cmp(&person.base) {
+                                    std::cmp::Ordering::Less => egui::Color32::LIGHT_RED,
+                                    std::cmp::Ordering::Equal => ui.visuals().text_color(),
+                                    std::cmp::Ordering::Greater => egui::Color32::LIGHT_GREEN,
+                                };
+                                ui.colored_label(color, person.tickets.to_string());
+                                ui.label(egui::RichText::new(&person.reason).small());
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+}
@@ -0,0 +1,190 @@
+use crate::history::{format_date, Draw, History};
+use eframe::egui;
+
+/// Filter text and which draws are opened or picked for comparing.
+#[derive(Default)]
+pub struct HistoryView {
+    pub filter: String,
+    open: Option<usize>,
+    compare: Vec<usize>, // Up to two draws
+}
+
+/// Sidebar section listing past draws (newest first) with per-person stats.
+/// Returns a status message when the history was exported.
+pub fn show(ui: &mut egui::Ui, history: &mut History, view: &mut HistoryView) -> Option<String> {
+    let mut status = None;
+    egui::CollapsingHeader::new(format!("Draw History ({})", history.draws.len()))
+        .id_salt("draw_history")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.text_edit_singleline(&mut view.filter)
+                    .on_hover_text("Person, roster or map");
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!history.draws.is_empty(), egui::Button::new("Export CSV"))
+                    .clicked()
+                {
+                    let result = crate::files::save_text(
+                        &crate::files::stamped_name("draw_history", "csv"),
+                        &history.to_csv(),
+                        "text/csv",
+                    );
+                    status = Some(result.unwrap_or_else(|e| e));
+                }
+                if let Some(i) = view.open {
+                    if ui.button("Delete Draw").clicked() {
+                        history.draws.remove(i);
+                        view.open = None;
+                        view.compare.clear();
+                    }
+                }
+            });
+
+            let filter = view.filter.to_lowercase();
+            egui::ScrollArea::vertical()
+                .id_salt("draw_history_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (i, draw) in history.draws.iter().enumerate().rev() {
+                        if !draw.matches(&filter) {
+                            continue;
+                        }
+                        ui.horizontal(|ui| {
+                            let mut picked = view.compare.contains(&i);
+                            if ui
+                                .checkbox(&mut picked, "")
+                                .on_hover_text("Compare")
+                                .changed()
+                            {
+                                view.compare.retain(|&j| j != i);
+                                if picked {
+                                    view.compare.push(i);
+                                    if view.compare.len() > 2 {
+                                        view.compare.remove(0);
+                                    }
+                                }
+                            }
+                            let label = format!(
+                                "{}  {}  🏆 {}",
+                                format_date(draw.timestamp),
+                                draw.roster,
+                                draw.winner().unwrap_or("-")
+                            );
+                            if ui.selectable_label(view.open == Some(i), label).clicked() {
+                                view.open = if view.open == Some(i) { None } else { Some(i) };
+                            }
+                        });
+                        if view.open == Some(i) {
+                            details(ui, i, draw);
+                        }
+                    }
+                });
+
+            if let [a, b] = view.compare[..] {
+                if let (Some(a), Some(b)) = (history.draws.get(a), history.draws.get(b)) {
+                    ui.separator();
+                    compare(ui, a, b);
+                }
+            }
+
+            ui.separator();
+            ui.label("Per person:");
+            egui::ScrollArea::vertical()
+                .id_salt("draw_stats_scroll")
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("draw_stats_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("Wins");
+                            ui.strong("Draws");
+                            ui.strong("Avg Rank");
+                            ui.strong("Last Win");
+                            ui.end_row();
+                            for person in history.stats() {
+                                ui.label(&person.name);
+                                ui.label(person.wins.to_string());
+                                ui.label(person.draws.to_string());
+                                ui.label(format!("{:.1}", person.average_rank));
+                                ui.label(person.last_win.map(format_date).unwrap_or_default());
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    status
+}
+
+fn details(ui: &mut egui::Ui, index: usize, draw: &Draw) {
+    ui.indent(("draw_details", index), |ui| {
+        ui.label(
+            egui::RichText::new(format!(
+                "Map: {}   Seed: {}   {}",
+                draw.map, draw.seed, draw.condition
+            ))
+            .small(),
+        );
+        for (i, placing) in draw.order.iter().enumerate() {
+            let mut line = format!("{}. {}", i + 1, placing.name);
+            if let Some(tickets) = draw.tickets_of(&placing.name).filter(|&t| t > 1) {
+                line.push_str(&format!(" ({} tickets)", tickets));
+            }
+            let mut text = egui::RichText::new(line);
+            if placing.eliminated {
+                text = text.strikethrough().weak();
+            }
+            ui.label(text);
+        }
+    });
+}
+
+/// Two draws side by side, with how far each person moved between them.
+fn compare(ui: &mut egui::Ui, a: &Draw, b: &Draw) {
+    ui.label(format!(
+        "{} vs {}",
+        format_date(a.timestamp),
+        format_date(b.timestamp)
+    ));
+    egui::Grid::new("draw_compare_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("#");
+            ui.strong("Earlier");
+            ui.strong("Later");
+            ui.strong("");
+            ui.end_row();
+            let (earlier, later) = if a.timestamp <= b.timestamp {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            for i in 0..earlier.order.len().max(later.order.len()) {
+                ui.label((i + 1).to_string());
+                ui.label(earlier.order.get(i).map_or("", |p| p.name.as_str()));
+                match later.order.get(i) {
+                    Some(placing) => {
+                        ui.label(&placing.name);
+                        match earlier.rank_of(&placing.name) {
+                            Some(before) if before > i + 1 => ui.colored_label(
+                                egui::Color32::GREEN,
+                                format!("▲{}", before - i - 1),
+                            ),
+                            Some(before) if before < i + 1 => {
+                                ui.colored_label(egui::Color32::RED, format!("▼{}", i + 1 - before))
+                            }
+                            Some(_) => ui.label("="),
+                            None => ui.label("new"),
+                        };
+                    }
+                    None => {
+                        ui.label("");
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
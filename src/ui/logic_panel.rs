@@ -0,0 +1,159 @@
+use crate::game::map_file::{Action, Link, MapElement, MapFile, Trigger};
+use crate::game::GameState;
+use eframe::egui;
+
+/// Sidebar section listing the map's links. New links come from the Link
+/// tool (or the button here for ones not tied to an object); each can be
+/// reconfigured or removed. Saved with the map file, and locked during a
+/// race like the rest of the map.
+pub fn show(ui: &mut egui::Ui, state: &mut GameState) {
+    egui::CollapsingHeader::new(format!("Logic Links ({})", state.map.links.len())).show(
+        ui,
+        |ui| {
+            let locked = state.race_in_progress();
+            if locked {
+                ui.label(egui::RichText::new("Locked until the race is over.").small());
+            }
+            ui.add_enabled_ui(!locked, |ui| links(ui, &mut state.map));
+        },
+    );
+}
+
+fn links(ui: &mut egui::Ui, map: &mut MapFile) {
+    let mut remove = None;
+    for i in 0..map.links.len() {
+        let mut link = map.links[i].clone();
+        ui.push_id(i, |ui| {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("When");
+                    trigger_editor(ui, map, &mut link.trigger);
+                    if ui.small_button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Then");
+                    action_editor(ui, map, &mut link.action);
+                });
+            });
+        });
+        map.links[i] = link;
+    }
+    if let Some(i) = remove {
+        map.links.remove(i);
+    }
+
+    if ui.button("Add Timed Link").clicked() {
+        map.links.push(Link {
+            trigger: Trigger::Time { seconds: 5.0 },
+            action: Action::Gravity {
+                gravity: map.physics.gravity / 2.0,
+            },
+        });
+    }
+    ui.label(
+        egui::RichText::new("Link tool: drag from a trigger object to a gate, spinner or pin.")
+            .small(),
+    );
+}
+
+fn trigger_editor(ui: &mut egui::Ui, map: &MapFile, trigger: &mut Trigger) {
+    let object = trigger.object().unwrap_or(0);
+    let mut kind = trigger.index();
+    egui::ComboBox::from_id_salt("trigger")
+        .selected_text(Trigger::NAMES[kind])
+        .show_index(ui, &mut kind, Trigger::NAMES.len(), |i| Trigger::NAMES[i]);
+    if kind != trigger.index() {
+        *trigger = match kind {
+            0 => Trigger::Entered { object },
+            1 => Trigger::Hits { object, count: 1 },
+            2 => Trigger::Finished { count: 1 },
+            _ => Trigger::Time { seconds: 5.0 },
+        };
+    }
+    match trigger {
+        Trigger::Entered { object } => object_picker(ui, map, object),
+        Trigger::Hits { object, count } => {
+            object_picker(ui, map, object);
+            ui.add(egui::DragValue::new(count).range(1..=99).prefix("×"));
+        }
+        Trigger::Finished { count } => {
+            ui.add(egui::DragValue::new(count).range(1..=99).prefix("#"));
+        }
+        Trigger::Time { seconds } => {
+            ui.add(
+                egui::DragValue::new(seconds)
+                    .range(0.0..=600.0)
+                    .speed(0.1)
+                    .suffix(" s"),
+            );
+        }
+    }
+}
+
+fn action_editor(ui: &mut egui::Ui, map: &MapFile, action: &mut Action) {
+    let object = action.object().unwrap_or(0);
+    let mut kind = action.index();
+    egui::ComboBox::from_id_salt("action")
+        .selected_text(Action::NAMES[kind])
+        .show_index(ui, &mut kind, Action::NAMES.len(), |i| Action::NAMES[i]);
+    if kind != action.index() {
+        *action = match kind {
+            0 => Action::OpenGate {
+                object,
+                seconds: 3.0,
+            },
+            1 => Action::Reverse { object },
+            2 => Action::SetLevel { object, level: 5 },
+            3 => Action::Spawn {
+                element: Box::new(MapElement::Bumper {
+                    x: 0.0,
+                    y: 0.0,
+                    radius: 13.0,
+                    level: 5,
+                }),
+            },
+            _ => Action::Gravity {
+                gravity: map.physics.gravity / 2.0,
+            },
+        };
+    }
+    match action {
+        Action::OpenGate { object, seconds } => {
+            object_picker(ui, map, object);
+            ui.add(
+                egui::DragValue::new(seconds)
+                    .range(0.1..=60.0)
+                    .speed(0.1)
+                    .suffix(" s"),
+            );
+        }
+        Action::Reverse { object } => object_picker(ui, map, object),
+        Action::SetLevel { object, level } => {
+            object_picker(ui, map, object);
+            ui.add(egui::DragValue::new(level).range(1..=5).prefix("Lv "));
+        }
+        Action::Spawn { element } => {
+            ui.label(element.name());
+            if let MapElement::Pin { x, y, .. } | MapElement::Bumper { x, y, .. } = element.as_mut()
+            {
+                ui.add(egui::DragValue::new(x).prefix("x "));
+                ui.add(egui::DragValue::new(y).prefix("y "));
+            }
+        }
+        Action::Gravity { gravity } => {
+            ui.add(egui::DragValue::new(gravity).range(-400.0..=200.0));
+        }
+    }
+}
+
+/// Pick a map object by id; shows its type so links stay readable.
+fn object_picker(ui: &mut egui::Ui, map: &MapFile, object: &mut u32) {
+    let name = map
+        .objects
+        .iter()
+        .find(|o| o.id == *object)
+        .map_or("Missing", |o| o.element.name());
+    ui.add(egui::DragValue::new(object).prefix(format!("{} #", name)));
+}
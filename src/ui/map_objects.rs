@@ -0,0 +1,656 @@
+use crate::game::map_file::{FieldKind, MapElement, PlatformPath, TrapKind, ZoneKind};
+use crate::game::GameState;
+use crate::ui::palette;
+use eframe::egui;
+
+const PATH_PREVIEW: egui::Color32 = egui::Color32::from_rgba_premultiplied(0, 120, 100, 120);
+const TRAP_RED: egui::Color32 = egui::Color32::from_rgb(
+    palette::TRAP_RED[0],
+    palette::TRAP_RED[1],
+    palette::TRAP_RED[2],
+);
+
+/// How long before switching on a laser starts blinking.
+const LASER_WARNING: f64 = 0.4;
+
+/// Map objects drawn from the map itself rather than from their colliders
+/// (sensors and fields aren't visible in the collider pass).
+pub fn paint(
+    painter: &egui::Painter,
+    state: &GameState,
+    time: f64,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) {
+    for object in &state.map.objects {
+        match object.element {
+            MapElement::Portal {
+                entry_x,
+                entry_y,
+                exit_x,
+                exit_y,
+                radius,
+                rotation,
+            } => {
+                let [r, g, b] = crate::game::portal_color(object.id);
+                let color = egui::Color32::from_rgb(r, g, b);
+                let entry = to_screen(entry_x, entry_y);
+                let exit = to_screen(exit_x, exit_y);
+
+                // Show which rings belong together while editing
+                if state.edit_mode {
+                    let link = egui::Color32::from_rgba_unmultiplied(r, g, b, 90);
+                    painter.extend(egui::Shape::dashed_line(
+                        &[entry, exit],
+                        egui::Stroke::new(1.0, link),
+                        6.0,
+                        4.0,
+                    ));
+                    if rotation.abs() > f32::EPSILON {
+                        painter.text(
+                            exit + egui::vec2(radius + 4.0, -radius),
+                            egui::Align2::LEFT_BOTTOM,
+                            format!("{:+.0}°", rotation),
+                            egui::FontId::proportional(10.0),
+                            color,
+                        );
+                    }
+                }
+
+                paint_ring(painter, entry, radius, color, time, true);
+                paint_ring(painter, exit, radius, color, time, false);
+            }
+            MapElement::Field {
+                x, y, radius, kind, ..
+            } => paint_field(painter, to_screen(x, y), radius, kind, time),
+            MapElement::Platform {
+                ref path, easing, ..
+            } if state.edit_mode => {
+                let points: Vec<egui::Pos2> = crate::game::motion::path_preview(path, easing, 64)
+                    .iter()
+                    .map(|p| to_screen(p[0], p[1]))
+                    .collect();
+                painter.extend(egui::Shape::dashed_line(
+                    &points,
+                    egui::Stroke::new(1.0, PATH_PREVIEW),
+                    6.0,
+                    4.0,
+                ));
+                if let PlatformPath::Waypoints { ref points, .. } = *path {
+                    for p in points {
+                        painter.circle_filled(to_screen(p[0], p[1]), 3.0, PATH_PREVIEW);
+                    }
+                }
+            }
+            MapElement::Block {
+                x,
+                y,
+                width,
+                height,
+                hits,
+            } => {
+                let damage = state.block_damage.get(&object.id).copied().unwrap_or(0);
+                if damage > 0 && damage < hits {
+                    paint_cracks(
+                        painter,
+                        object.id,
+                        [x, y],
+                        [width, height],
+                        damage,
+                        to_screen,
+                    );
+                }
+            }
+            MapElement::Trap {
+                x,
+                y,
+                trap: TrapKind::Pit { radius },
+            } => paint_pit(painter, to_screen(x, y), radius, time),
+            MapElement::Trap {
+                x,
+                y,
+                trap:
+                    TrapKind::Laser {
+                        length,
+                        angle,
+                        on,
+                        off,
+                    },
+            } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let (hx, hy) = (cos * length / 2.0, sin * length / 2.0);
+                let a = to_screen(x - hx, y - hy);
+                let b = to_screen(x + hx, y + hy);
+                let t = state.sim_time;
+                if crate::game::motion::laser_on(on, off, t) {
+                    let flicker = ((time * 40.0).sin() * 0.5 + 0.5) as f32;
+                    painter.line_segment(
+                        [a, b],
+                        egui::Stroke::new(
+                            6.0 + flicker * 2.0,
+                            egui::Color32::from_rgba_unmultiplied(255, 0, 0, 60),
+                        ),
+                    );
+                    painter.line_segment([a, b], egui::Stroke::new(2.0, TRAP_RED));
+                    painter.line_segment([a, b], egui::Stroke::new(0.5, egui::Color32::WHITE));
+                } else {
+                    let warning = crate::game::motion::laser_on(on, off, t + LASER_WARNING)
+                        && (time * 12.0).sin() > 0.0;
+                    let color = if warning {
+                        TRAP_RED
+                    } else {
+                        egui::Color32::from_rgba_unmultiplied(255, 40, 40, 50)
+                    };
+                    painter.extend(egui::Shape::dashed_line(
+                        &[a, b],
+                        egui::Stroke::new(1.0, color),
+                        4.0,
+                        4.0,
+                    ));
+                }
+                // Emitters
+                for p in [a, b] {
+                    painter.circle_filled(p, 4.0, egui::Color32::from_gray(90));
+                    painter.circle_stroke(p, 4.0, egui::Stroke::new(1.0, TRAP_RED));
+                }
+            }
+            MapElement::Trap {
+                x,
+                y,
+                trap: TrapKind::Crusher { width, drop, .. },
+            } if state.edit_mode => {
+                // Where it slams down to
+                let (hw, hh) = (width / 2.0, 10.0);
+                painter.rect_stroke(
+                    egui::Rect::from_two_pos(
+                        to_screen(x - hw, y - drop + hh),
+                        to_screen(x + hw, y - drop - hh),
+                    ),
+                    0.0,
+                    egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 40, 40, 90)),
+                );
+            }
+            MapElement::Rope { x, y, .. } | MapElement::Pendulum { x, y, .. } => {
+                paint_anchor(painter, to_screen(x, y));
+            }
+            MapElement::Chain { x1, y1, x2, y2, .. } => {
+                paint_anchor(painter, to_screen(x1, y1));
+                paint_anchor(painter, to_screen(x2, y2));
+            }
+            MapElement::Wheel {
+                x, y, radius, cups, ..
+            } => {
+                // Spokes out to the cups, turning with the wheel body
+                let turn = state
+                    .object_colliders(object.id)
+                    .first()
+                    .and_then(|&c| state.physics.collider_set.get(c)?.parent())
+                    .and_then(|b| state.physics.rigid_body_set.get(b))
+                    .map_or(0.0, |rb| rb.rotation().angle());
+                let hub = to_screen(x, y);
+                let spoke = egui::Stroke::new(2.0, egui::Color32::from_rgb(40, 110, 150));
+                for i in 0..cups {
+                    let a = turn + i as f32 / cups as f32 * std::f32::consts::TAU;
+                    let rim = to_screen(x + a.cos() * radius, y + a.sin() * radius);
+                    painter.line_segment([hub, rim], spoke);
+                }
+                painter.circle_stroke(hub, radius, egui::Stroke::new(1.0, spoke.color));
+                paint_anchor(painter, hub);
+            }
+            MapElement::Tulip { x, y, trigger, .. } => {
+                let opening = state
+                    .tulip_wings
+                    .get(&object.id)
+                    .map_or(0.0, |&(opened, closes)| {
+                        crate::game::motion::wing_opening(opened, closes, state.sim_time)
+                    });
+                let [pocket, _] = crate::game::maps::tulip_pocket(x, y);
+                let center = to_screen(pocket.x, pocket.y);
+                // Glows brighter the wider the wings are
+                painter.circle_filled(
+                    center,
+                    10.0 + opening * 6.0,
+                    egui::Color32::from_rgba_unmultiplied(
+                        255,
+                        120,
+                        170,
+                        (40.0 + opening * 100.0) as u8,
+                    ),
+                );
+                if state.edit_mode {
+                    let target = trigger.and_then(|id| object_center(state, id, to_screen));
+                    if let Some(target) = target {
+                        painter.extend(egui::Shape::dashed_line(
+                            &[center, target],
+                            egui::Stroke::new(1.0, palette::rgb(palette::TULIP)),
+                            6.0,
+                            4.0,
+                        ));
+                        painter.circle_stroke(
+                            target,
+                            6.0,
+                            egui::Stroke::new(1.0, palette::rgb(palette::TULIP)),
+                        );
+                    }
+                }
+            }
+            MapElement::Splitter { x, y, radius } => {
+                painter.text(
+                    to_screen(x, y),
+                    egui::Align2::CENTER_CENTER,
+                    "×2",
+                    egui::FontId::proportional(radius),
+                    egui::Color32::from_rgb(20, 20, 30),
+                );
+            }
+            MapElement::Gate { x1, y1, x2, y2, .. } if state.edit_mode => {
+                // Ghost of the gate fully open
+                let (dx, dy) = (x2 - x1, y2 - y1);
+                painter.extend(egui::Shape::dashed_line(
+                    &[to_screen(x1 - dx, y1 - dy), to_screen(x1, y1)],
+                    egui::Stroke::new(3.0, PATH_PREVIEW),
+                    6.0,
+                    4.0,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    // Logic links, trigger to action
+    if state.edit_mode {
+        let color = egui::Color32::from_rgb(120, 220, 255);
+        for link in &state.map.links {
+            let (Some(from), Some(to)) = (link.trigger.object(), link.action.object()) else {
+                continue;
+            };
+            let (Some(a), Some(b)) = (
+                object_center(state, from, to_screen),
+                object_center(state, to, to_screen),
+            ) else {
+                continue;
+            };
+            painter.extend(egui::Shape::dashed_line(
+                &[a, b],
+                egui::Stroke::new(1.5, color),
+                8.0,
+                4.0,
+            ));
+            // Arrowhead at the acting end
+            let dir = (b - a).normalized();
+            let side = egui::vec2(-dir.y, dir.x);
+            let tip = b - dir * 8.0;
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    tip,
+                    tip - dir * 8.0 + side * 4.0,
+                    tip - dir * 8.0 - side * 4.0,
+                ],
+                color,
+                egui::Stroke::NONE,
+            ));
+        }
+    }
+
+    // Waypoints clicked so far for a new platform
+    if state.edit_mode && !state.editor_waypoints.is_empty() {
+        let points: Vec<egui::Pos2> = state
+            .editor_waypoints
+            .iter()
+            .map(|p| to_screen(p[0], p[1]))
+            .collect();
+        for &p in &points {
+            painter.circle_filled(p, 4.0, egui::Color32::YELLOW);
+        }
+        painter.add(egui::Shape::line(
+            points,
+            egui::Stroke::new(1.5, egui::Color32::YELLOW),
+        ));
+    }
+
+    // Countdown arc around balls held in a tulip
+    for capture in state.pocketed.values() {
+        let Some(&MapElement::Tulip { x, y, hold, .. }) = state
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == capture.object)
+            .map(|o| &o.element)
+        else {
+            continue;
+        };
+        let left = ((capture.release_at - state.sim_time) / hold as f64).clamp(0.0, 1.0) as f32;
+        let [pocket, _] = crate::game::maps::tulip_pocket(x, y);
+        paint_countdown(
+            painter,
+            to_screen(pocket.x, pocket.y),
+            state.map.physics.ball_radius + 5.0,
+            left,
+        );
+    }
+
+    // Countdown arc around balls held in a black hole
+    for capture in state.captured.values() {
+        let Some(MapElement::Field { x, y, .. }) = state
+            .map
+            .objects
+            .iter()
+            .find(|o| o.id == capture.object)
+            .map(|o| &o.element)
+        else {
+            continue;
+        };
+        let left = ((capture.release_at - state.sim_time) / crate::game::BLACK_HOLE_HOLD)
+            .clamp(0.0, 1.0) as f32;
+        paint_countdown(
+            painter,
+            to_screen(*x, *y),
+            state.map.physics.ball_radius + 5.0,
+            left,
+        );
+    }
+}
+
+/// Arc clockwise from 12 o'clock covering the `left` share of a full turn.
+fn paint_countdown(painter: &egui::Painter, center: egui::Pos2, radius: f32, left: f32) {
+    let arc: Vec<egui::Pos2> = (0..=24)
+        .map(|i| {
+            let a = -std::f32::consts::FRAC_PI_2 + i as f32 / 24.0 * left * std::f32::consts::TAU;
+            center + egui::vec2(radius * a.cos(), radius * a.sin())
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        arc,
+        egui::Stroke::new(2.0, egui::Color32::WHITE),
+    ));
+}
+
+/// Screen position of map object `id` (its first collider).
+fn object_center(
+    state: &GameState,
+    id: u32,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) -> Option<egui::Pos2> {
+    let &collider = state.object_colliders(id).first()?;
+    let t = state.physics.collider_set.get(collider)?.translation();
+    Some(to_screen(t.x, t.y))
+}
+
+/// Bolt a rope, chain, pendulum or wheel hangs from.
+fn paint_anchor(painter: &egui::Painter, at: egui::Pos2) {
+    painter.circle_filled(at, 4.0, egui::Color32::from_gray(120));
+    painter.circle_stroke(
+        at,
+        4.0,
+        egui::Stroke::new(1.0, egui::Color32::from_gray(200)),
+    );
+}
+
+/// Dark hole with a slowly turning red rim.
+fn paint_pit(painter: &egui::Painter, center: egui::Pos2, radius: f32, time: f64) {
+    painter.circle_filled(center, radius, palette::rgb(palette::PIT));
+    painter.circle_stroke(
+        center,
+        radius,
+        egui::Stroke::new(2.0, palette::rgb(palette::PIT_RIM)),
+    );
+    let spin = time as f32 * 1.5;
+    for i in 0..3 {
+        let start = spin + i as f32 * std::f32::consts::TAU / 3.0;
+        let arc: Vec<egui::Pos2> = (0..=8)
+            .map(|j| {
+                let a = start + j as f32 * 0.12;
+                center + egui::vec2(a.cos(), a.sin()) * (radius - 3.0)
+            })
+            .collect();
+        painter.add(egui::Shape::line(arc, egui::Stroke::new(1.5, TRAP_RED)));
+    }
+}
+
+/// Jagged cracks from the edges towards the middle of a block, three more per
+/// hit taken. Seeded by the object id so they don't flicker between frames.
+fn paint_cracks(
+    painter: &egui::Painter,
+    id: u32,
+    center: [f32; 2],
+    size: [f32; 2],
+    damage: u32,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) {
+    let mut seed = id.wrapping_mul(2654435761).wrapping_add(1);
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed % 1000) as f32 / 1000.0 - 0.5 // -0.5..0.5
+    };
+
+    let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(30, 10, 50));
+    let (hw, hh) = (size[0] / 2.0, size[1] / 2.0);
+    for _ in 0..damage * 3 {
+        // Start on a random edge point, wander inwards
+        let (mut x, mut y) = if next() > 0.0 {
+            (next() * size[0], hh * next().signum())
+        } else {
+            (hw * next().signum(), next() * size[1])
+        };
+        let mut points = vec![to_screen(center[0] + x, center[1] + y)];
+        for _ in 0..3 {
+            x = (x * 0.5 + next() * hw * 0.4).clamp(-hw, hw);
+            y = (y * 0.5 + next() * hh * 0.4).clamp(-hh, hh);
+            points.push(to_screen(center[0] + x, center[1] + y));
+        }
+        painter.add(egui::Shape::line(points, stroke));
+    }
+}
+
+/// Zones go underneath everything else, so they are painted separately
+/// before the colliders.
+pub fn paint_zones(
+    painter: &egui::Painter,
+    state: &GameState,
+    time: f64,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) {
+    for object in &state.map.objects {
+        if let MapElement::Zone {
+            ref points,
+            kind,
+            angle,
+            ..
+        } = object.element
+        {
+            paint_zone(painter, points, kind, angle, time, to_screen);
+        }
+    }
+}
+
+/// Translucent polygon with an animated pattern: streaks drifting with the
+/// wind, chevrons running along a boost, bubbles in mud, glints on ice.
+fn paint_zone(
+    painter: &egui::Painter,
+    points: &[[f32; 2]],
+    kind: ZoneKind,
+    angle: f32,
+    time: f64,
+    to_screen: &dyn Fn(f32, f32) -> egui::Pos2,
+) {
+    if points.len() < 3 {
+        return;
+    }
+    let [r, g, b] = palette::zone(kind);
+    let with_alpha = |a: f32| egui::Color32::from_rgba_unmultiplied(r, g, b, a as u8);
+
+    let pulse = ((time * 2.0).sin() * 0.5 + 0.5) as f32;
+    painter.add(egui::Shape::convex_polygon(
+        points.iter().map(|p| to_screen(p[0], p[1])).collect(),
+        with_alpha(30.0 + 15.0 * pulse),
+        egui::Stroke::new(1.0, with_alpha(120.0)),
+    ));
+
+    // Pattern on a grid over the bounding box, kept inside the polygon
+    let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+    for p in points {
+        min_x = min_x.min(p[0]);
+        min_y = min_y.min(p[1]);
+        max_x = max_x.max(p[0]);
+        max_y = max_y.max(p[1]);
+    }
+    let spacing = 28.0;
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (speed, length) = match kind {
+        ZoneKind::Wind => (40.0, 10.0),
+        ZoneKind::Boost => (80.0, 6.0),
+        ZoneKind::Mud | ZoneKind::Ice => (0.0, 0.0),
+    };
+    let drift = (time as f32 * speed) % spacing;
+
+    let mut i = 0;
+    let mut y = min_y + spacing / 2.0;
+    while y < max_y {
+        let mut x = min_x + spacing / 2.0;
+        while x < max_x {
+            i += 1;
+            let (px, py) = (x + cos * drift, y + sin * drift);
+            let center = to_screen(px, py);
+            let dir = egui::vec2(cos, -sin); // Screen y is down
+            match kind {
+                ZoneKind::Wind if contains(points, px, py) => {
+                    painter.line_segment(
+                        [center - dir * length, center + dir * length],
+                        egui::Stroke::new(1.5, with_alpha(140.0)),
+                    );
+                }
+                ZoneKind::Boost if contains(points, px, py) => {
+                    let side = egui::vec2(-dir.y, dir.x) * length;
+                    painter.add(egui::Shape::line(
+                        vec![
+                            center - dir * length + side,
+                            center,
+                            center - dir * length - side,
+                        ],
+                        egui::Stroke::new(2.0, with_alpha(200.0)),
+                    ));
+                }
+                ZoneKind::Mud if contains(points, x, y) => {
+                    let phase = (time * 0.5 + i as f64 * 0.37).fract() as f32;
+                    painter.circle_stroke(
+                        center,
+                        2.0 + 5.0 * phase,
+                        egui::Stroke::new(1.0, with_alpha(160.0 * (1.0 - phase))),
+                    );
+                }
+                ZoneKind::Ice if contains(points, x, y) => {
+                    let glint = ((time * 1.5 + i as f64 * 1.7).sin() * 0.5 + 0.5) as f32;
+                    let arm = 2.0 + 3.0 * glint;
+                    let stroke = egui::Stroke::new(1.0, with_alpha(200.0 * glint));
+                    painter.line_segment(
+                        [center - egui::vec2(arm, 0.0), center + egui::vec2(arm, 0.0)],
+                        stroke,
+                    );
+                    painter.line_segment(
+                        [center - egui::vec2(0.0, arm), center + egui::vec2(0.0, arm)],
+                        stroke,
+                    );
+                }
+                _ => {}
+            }
+            x += spacing;
+        }
+        y += spacing;
+    }
+}
+
+/// Even-odd point-in-polygon test in world coordinates.
+fn contains(points: &[[f32; 2]], x: f32, y: f32) -> bool {
+    let mut inside = false;
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        let (xi, yi) = (points[i][0], points[i][1]);
+        let (xj, yj) = (points[j][0], points[j][1]);
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Faint disc for the area of effect, with rings running towards the centre
+/// (pull) or away from it (push).
+fn paint_field(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    radius: f32,
+    kind: FieldKind,
+    time: f64,
+) {
+    let [r, g, b] = palette::field(kind);
+    let color = egui::Color32::from_rgb(r, g, b);
+
+    painter.circle(
+        center,
+        radius,
+        egui::Color32::from_rgba_unmultiplied(r, g, b, 15),
+        egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(r, g, b, 80)),
+    );
+
+    for i in 0..3 {
+        let phase = ((time * 0.6) + i as f64 / 3.0).fract() as f32;
+        let t = match kind {
+            FieldKind::Repulsor => phase,
+            FieldKind::Attractor | FieldKind::BlackHole => 1.0 - phase,
+        };
+        let alpha = (120.0 * t.min(1.0 - t) * 2.0) as u8;
+        painter.circle_stroke(
+            center,
+            radius * t,
+            egui::Stroke::new(1.5, egui::Color32::from_rgba_unmultiplied(r, g, b, alpha)),
+        );
+    }
+
+    let core = crate::game::maps::BLACK_HOLE_CORE_RADIUS;
+    if kind == FieldKind::BlackHole {
+        let pulse = ((time * 4.0).sin() * 0.5 + 0.5) as f32;
+        painter.circle_filled(center, core + 4.0 + pulse * 3.0, color);
+        painter.circle_filled(center, core, egui::Color32::BLACK);
+    } else {
+        painter.circle_filled(center, core * 0.6, color);
+    }
+}
+
+/// Spinning dashed ring with pulses running inwards (entry) or outwards (exit).
+fn paint_ring(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    radius: f32,
+    color: egui::Color32,
+    time: f64,
+    entry: bool,
+) {
+    let fill = egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), 40);
+    painter.circle_filled(center, radius, fill);
+
+    let dir = if entry { 1.0 } else { -1.0 };
+    let spin = (time * 2.0) as f32 * dir;
+    for i in 0..3 {
+        let start = spin + i as f32 / 3.0 * std::f32::consts::TAU;
+        let arc: Vec<egui::Pos2> = (0..=8)
+            .map(|j| {
+                let a = start + j as f32 / 8.0 * 1.4;
+                center + egui::vec2(radius * a.cos(), radius * a.sin())
+            })
+            .collect();
+        painter.add(egui::Shape::line(arc, egui::Stroke::new(2.5, color)));
+    }
+
+    let phase = (time * 1.5).fract() as f32;
+    let t = if entry { 1.0 - phase } else { phase };
+    let pulse = egui::Color32::from_rgba_unmultiplied(
+        color.r(),
+        color.g(),
+        color.b(),
+        (200.0 * (1.0 - phase)) as u8,
+    );
+    painter.circle_stroke(center, radius * t, egui::Stroke::new(1.5, pulse));
+}
@@ -0,0 +1,16 @@
+pub mod audit_panel;
+pub mod debug_overlay;
+pub mod fairness_panel;
+pub mod history_panel;
+pub mod logic_panel;
+pub mod map_objects;
+pub mod palette;
+pub mod photo_finish;
+pub mod physics_panel;
+pub mod roster_panel;
+pub mod skins;
+pub mod tournament_panel;
+pub mod verify_panel;
+
+/// The UI font (with Korean support), also used for exported images.
+pub const FONT: &[u8] = include_bytes!("../../assets/fonts/ChungBuk_70_Regular.ttf");
@@ -0,0 +1,68 @@
+//! Colours for map objects, shared by the live canvas and the results
+//! certificate so the two draw the board alike.
+
+use crate::game::map_file::{FieldKind, ZoneKind};
+use crate::game::maps;
+
+/// Fill colour for a collider, by its `user_data` tag. `None` for tags
+/// without one of their own (the caller picks a neutral fallback).
+pub fn collider(user_data: u128) -> Option<[u8; 3]> {
+    if (user_data >> 64) & 1 == 1 {
+        // Event obstacle: colour packed into the tag
+        return Some([
+            (user_data >> 48) as u8,
+            (user_data >> 40) as u8,
+            (user_data >> 32) as u8,
+        ]);
+    }
+    let color = match user_data {
+        11 => [0, 100, 255],                // Level 1: Blue
+        12 => [50, 255, 50],                // Level 2: Green
+        13 => [255, 255, 0],                // Level 3: Yellow
+        14 => [255, 165, 0],                // Level 4: Orange
+        15 => [255, 50, 50],                // Level 5: Red
+        99 | 21 => [0, 255, 255],           // Goal / Slow - Cyan
+        22 => [255, 0, 255],                // Normal - Magenta
+        23 => [128, 0, 128],                // Fast - Purple
+        maps::PLATFORM => [0, 200, 160],    // Teal
+        maps::GATE => [255, 190, 0],        // Amber
+        maps::BLOCK => [170, 120, 255],     // Violet
+        maps::ONE_WAY => [255, 100, 200],   // Pink
+        maps::DROP_TARGET => [255, 90, 60], // Orange-Red
+        maps::TRAP => [200, 30, 30],        // Crusher - Blood Red
+        maps::TULIP => TULIP,
+        maps::WHEEL => [90, 200, 255],     // Wheel cups - Sky
+        maps::SPLITTER => [220, 220, 240], // Silver
+        maps::PENDULUM => [150, 160, 180], // Steel
+        maps::LINK => [190, 150, 100],     // Hemp
+        _ => return None,
+    };
+    Some(color)
+}
+
+pub fn zone(kind: ZoneKind) -> [u8; 3] {
+    match kind {
+        ZoneKind::Wind => [150, 220, 255],
+        ZoneKind::Mud => [140, 90, 40],
+        ZoneKind::Ice => [200, 245, 255],
+        ZoneKind::Boost => [80, 255, 120],
+    }
+}
+
+pub fn field(kind: FieldKind) -> [u8; 3] {
+    match kind {
+        FieldKind::Attractor => [80, 160, 255],
+        FieldKind::Repulsor => [255, 140, 60],
+        FieldKind::BlackHole => [170, 60, 255],
+    }
+}
+
+/// An RGB triple as an egui colour.
+pub fn rgb([r, g, b]: [u8; 3]) -> eframe::egui::Color32 {
+    eframe::egui::Color32::from_rgb(r, g, b)
+}
+
+pub const TULIP: [u8; 3] = [255, 120, 170]; // Petal Pink
+pub const TRAP_RED: [u8; 3] = [255, 40, 40]; // Lasers and pit markings
+pub const PIT: [u8; 3] = [5, 0, 0];
+pub const PIT_RIM: [u8; 3] = [140, 0, 0];
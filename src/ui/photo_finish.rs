@@ -0,0 +1,167 @@
+use crate::game::GameState;
+use eframe::egui;
+use rapier2d::prelude::*;
+
+// World units shown around the finish line, and their on-screen scale.
+const VIEW_HALF: f32 = 60.0;
+const VIEW_SCALE: f32 = 2.5;
+
+/// Frozen "photo finish" frame for close finishes, shown after the race.
+pub fn show(ctx: &egui::Context, state: &GameState, open: &mut bool, selected: &mut usize) {
+    if state.photo_finishes.is_empty() {
+        return;
+    }
+    *selected = (*selected).min(state.photo_finishes.len() - 1);
+
+    egui::Window::new("Photo Finish")
+        .open(open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            if state.photo_finishes.len() > 1 {
+                ui.horizontal(|ui| {
+                    if ui.button("<").clicked() && *selected > 0 {
+                        *selected -= 1;
+                    }
+                    ui.label(format!(
+                        "Close finish {} of {}",
+                        *selected + 1,
+                        state.photo_finishes.len()
+                    ));
+                    if ui.button(">").clicked() && *selected + 1 < state.photo_finishes.len() {
+                        *selected += 1;
+                    }
+                });
+            }
+
+            let photo = &state.photo_finishes[*selected];
+            let radius = state.map.physics.ball_radius * VIEW_SCALE;
+            let shutter = photo.shutter_time(&state.finished_balls);
+
+            let size = egui::vec2(VIEW_HALF * 2.0 * VIEW_SCALE, VIEW_HALF * 2.0 * VIEW_SCALE);
+            let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+            let rect = response.rect;
+            painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(10, 10, 15));
+
+            let center = rect.center();
+            let to_screen = |x: f32, y: f32| -> egui::Pos2 {
+                egui::pos2(
+                    center.x + x * VIEW_SCALE,
+                    center.y - (y - photo.finish_y) * VIEW_SCALE,
+                )
+            };
+            let painter = painter.with_clip_rect(rect);
+
+            // Fixed geometry around the line (chute walls, funnel)
+            let view = Aabb::new(
+                point![-VIEW_HALF, photo.finish_y - VIEW_HALF],
+                point![VIEW_HALF, photo.finish_y + VIEW_HALF],
+            );
+            for (_handle, collider) in state.physics.collider_set.iter() {
+                if collider.parent().is_some() || collider.is_sensor() {
+                    continue;
+                }
+                if !collider.compute_aabb().intersects(&view) {
+                    continue;
+                }
+                if let Some(cuboid) = collider.shape().as_cuboid() {
+                    let hx = cuboid.half_extents.x;
+                    let hy = cuboid.half_extents.y;
+                    let points = [
+                        point![-hx, -hy],
+                        point![hx, -hy],
+                        point![hx, hy],
+                        point![-hx, hy],
+                    ]
+                    .iter()
+                    .map(|p| {
+                        let world_p = collider.position() * p;
+                        to_screen(world_p.x, world_p.y)
+                    })
+                    .collect();
+                    painter.add(egui::Shape::convex_polygon(
+                        points,
+                        egui::Color32::DARK_GRAY,
+                        egui::Stroke::NONE,
+                    ));
+                }
+            }
+
+            // Checkered finish line
+            let left = to_screen(-VIEW_HALF, photo.finish_y);
+            let checks = 24;
+            let check_w = rect.width() / checks as f32;
+            for i in 0..checks {
+                let color = if i % 2 == 0 {
+                    egui::Color32::WHITE
+                } else {
+                    egui::Color32::BLACK
+                };
+                painter.rect_filled(
+                    egui::Rect::from_min_size(
+                        egui::pos2(left.x + i as f32 * check_w, left.y - 1.5),
+                        egui::vec2(check_w, 3.0),
+                    ),
+                    0.0,
+                    color,
+                );
+            }
+
+            // Balls still racing (dimmed)
+            for other in &photo.others {
+                let color = egui::Color32::from_rgba_unmultiplied(
+                    other.color[0],
+                    other.color[1],
+                    other.color[2],
+                    90,
+                );
+                painter.circle_filled(to_screen(other.x, other.y), radius, color);
+            }
+
+            // The finishers, frozen at the moment the last one crossed
+            for &i in &photo.entries {
+                let ball = &state.finished_balls[i];
+                let (x, y) = photo.position_of(ball, shutter);
+                let pos = to_screen(x, y);
+                painter.circle(
+                    pos,
+                    radius,
+                    egui::Color32::from_rgb(ball.color[0], ball.color[1], ball.color[2]),
+                    egui::Stroke::new(1.5, egui::Color32::WHITE),
+                );
+                painter.text(
+                    pos,
+                    egui::Align2::CENTER_CENTER,
+                    format!("{}", i + 1),
+                    egui::FontId::proportional(14.0),
+                    egui::Color32::BLACK,
+                );
+            }
+
+            // Crossing order and deltas
+            ui.separator();
+            let first = photo
+                .entries
+                .first()
+                .map(|&i| state.finished_balls[i].crossed_at)
+                .unwrap_or(0.0);
+            egui::Grid::new("photo_finish_order").show(ui, |ui| {
+                for &i in &photo.entries {
+                    let ball = &state.finished_balls[i];
+                    ui.label(format!("{}.", i + 1));
+                    ui.colored_label(
+                        egui::Color32::from_rgb(ball.color[0], ball.color[1], ball.color[2]),
+                        &ball.name,
+                    );
+                    ui.label(format!("{:.3}s", ball.crossed_at));
+                    let delta = ball.crossed_at - first;
+                    if delta > 0.0 {
+                        ui.label(format!("+{:.3}s", delta));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
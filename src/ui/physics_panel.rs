@@ -0,0 +1,67 @@
+use crate::game::settings::{PhysicsSettings, Preset};
+use crate::game::GameState;
+use eframe::egui;
+
+/// Sidebar section for `PhysicsSettings`. Changes apply to the world
+/// immediately and are saved with the map file. Locked during a race, whose
+/// commitment covers the map as it started.
+pub fn show(ui: &mut egui::Ui, state: &mut GameState) {
+    egui::CollapsingHeader::new("Physics Settings").show(ui, |ui| {
+        if state.is_running {
+            ui.label(egui::RichText::new("Locked until the race is reset.").small());
+        }
+        ui.add_enabled_ui(!state.is_running, |ui| controls(ui, state));
+    });
+}
+
+fn controls(ui: &mut egui::Ui, state: &mut GameState) {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        for preset in Preset::ALL {
+            let selected = state.map.physics == PhysicsSettings::preset(preset);
+            if ui.selectable_label(selected, preset.name()).clicked() {
+                state.map.physics = PhysicsSettings::preset(preset);
+                changed = true;
+            }
+        }
+    });
+
+    let settings = &mut state.map.physics;
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.gravity, -200.0..=0.0).text("Gravity"))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.max_speed, 500.0..=8000.0).text("Max Speed"))
+        .changed();
+
+    ui.label("Balls:");
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.ball_radius, 4.0..=14.0).text("Radius"))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.ball_restitution, 0.0..=1.2).text("Restitution"))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.ball_linear_damping, 0.0..=2.0).text("Damping"))
+        .changed();
+
+    ui.label("Pin Elasticity (Level 1-5):");
+    for (i, level) in settings.elasticity_levels.iter_mut().enumerate() {
+        changed |= ui
+            .add(egui::Slider::new(level, 0.0..=6.0).text(format!("Level {}", i + 1)))
+            .changed();
+    }
+
+    ui.label("Solver:");
+    changed |= ui
+        .add(egui::Slider::new(&mut settings.solver_iterations, 1..=16).text("Iterations"))
+        .changed();
+    changed |= ui
+        .checkbox(&mut settings.ccd_enabled, "CCD (prevent tunneling)")
+        .changed();
+
+    if changed {
+        state.apply_physics_settings();
+    }
+}
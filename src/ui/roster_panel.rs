@@ -0,0 +1,228 @@
+use crate::roster::{Entry, Roster, Rosters};
+use eframe::egui;
+
+const ERROR_RED: egui::Color32 = egui::Color32::from_rgb(255, 110, 110);
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum SortBy {
+    #[default]
+    List,
+    Name,
+    Tickets,
+    Team,
+}
+
+/// Search and sort for the people list, plus the name being typed in.
+#[derive(Default)]
+pub struct RosterView {
+    pub search: String,
+    pub sort: SortBy,
+    new_person: String,
+}
+
+/// Saved rosters: switch between them, add and delete them, and edit who is
+/// in the active one. Returns true when the active roster's people changed
+/// (or a different roster was picked).
+pub fn manager(ui: &mut egui::Ui, rosters: &mut Rosters, view: &mut RosterView) -> bool {
+    let mut changed = false;
+    egui::CollapsingHeader::new("Saved Rosters")
+        .id_salt("saved_rosters")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let before = rosters.active;
+                egui::ComboBox::from_id_salt("active_roster")
+                    .selected_text(rosters.active().name.clone())
+                    .show_ui(ui, |ui| {
+                        for (i, roster) in rosters.saved.iter().enumerate() {
+                            ui.selectable_value(&mut rosters.active, i, &roster.name);
+                        }
+                    });
+                changed |= rosters.active != before;
+                if ui.button("New").clicked() {
+                    rosters.add(format!("Roster {}", rosters.saved.len() + 1));
+                    changed = true;
+                }
+                if ui
+                    .add_enabled(rosters.saved.len() > 1, egui::Button::new("Delete"))
+                    .clicked()
+                {
+                    rosters.remove_active();
+                    changed = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut rosters.active_mut().name);
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.text_edit_singleline(&mut view.search);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Sort:");
+                ui.selectable_value(&mut view.sort, SortBy::List, "List");
+                ui.selectable_value(&mut view.sort, SortBy::Name, "Name");
+                ui.selectable_value(&mut view.sort, SortBy::Tickets, "Tickets");
+                ui.selectable_value(&mut view.sort, SortBy::Team, "Team");
+            });
+
+            let entries = &mut rosters.active_mut().entries;
+            ui.horizontal(|ui| {
+                let present = entries.iter().filter(|e| !e.excluded).count();
+                ui.label(format!("{} of {} present", present, entries.len()));
+                if ui.small_button("All").clicked() {
+                    entries.iter_mut().for_each(|e| e.excluded = false);
+                    changed = true;
+                }
+                if ui.small_button("None").clicked() {
+                    entries.iter_mut().for_each(|e| e.excluded = true);
+                    changed = true;
+                }
+            });
+
+            let shown = visible(entries, view);
+            let mut removed = None;
+            let row_height = ui.spacing().interact_size.y;
+            egui::ScrollArea::vertical()
+                .id_salt("saved_roster_scroll")
+                .max_height(240.0)
+                .show_rows(ui, row_height, shown.len(), |ui, range| {
+                    for &i in &shown[range] {
+                        let entry = &mut entries[i];
+                        ui.horizontal(|ui| {
+                            let mut present = !entry.excluded;
+                            if ui.checkbox(&mut present, &entry.name).changed() {
+                                entry.excluded = !present;
+                                changed = true;
+                            }
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut entry.tickets)
+                                        .range(1..=999)
+                                        .suffix(" 🎟"),
+                                )
+                                .changed();
+                            if let Some(team) = &entry.team {
+                                ui.label(egui::RichText::new(team).weak());
+                            }
+                            if ui.small_button("🗑").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                    }
+                });
+            if let Some(i) = removed {
+                entries.remove(i);
+                changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                let response = ui.text_edit_singleline(&mut view.new_person);
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let name = view.new_person.trim().to_owned();
+                let duplicate = entries.iter().any(|e| e.name.eq_ignore_ascii_case(&name));
+                if (ui.button("Add Person").clicked() || entered) && !name.is_empty() && !duplicate
+                {
+                    entries.push(Entry::new(&name));
+                    view.new_person.clear();
+                    changed = true;
+                }
+            });
+        });
+    changed
+}
+
+/// Indices of the entries matching the search, in the chosen order.
+fn visible(entries: &[Entry], view: &RosterView) -> Vec<usize> {
+    let search = view.search.to_lowercase();
+    let mut shown: Vec<usize> = (0..entries.len())
+        .filter(|&i| {
+            let e = &entries[i];
+            e.name.to_lowercase().contains(&search)
+                || e.team
+                    .as_ref()
+                    .is_some_and(|t| t.to_lowercase().contains(&search))
+        })
+        .collect();
+    match view.sort {
+        SortBy::List => {}
+        SortBy::Name => shown.sort_by_key(|&i| entries[i].name.to_lowercase()),
+        SortBy::Tickets => shown.sort_by_key(|&i| std::cmp::Reverse(entries[i].tickets)),
+        SortBy::Team => shown.sort_by_key(|&i| entries[i].team.clone()),
+    }
+    shown
+}
+
+/// Preview of the parsed participant list, with each row's problems listed
+/// next to it so they can be fixed before starting.
+pub fn show(ui: &mut egui::Ui, roster: &Roster) {
+    let entrants = roster.entrants().count();
+    let errors = roster.error_count();
+    let title = format!(
+        "Roster Preview ({} people, {} tickets)",
+        entrants,
+        roster.ticket_total()
+    );
+    egui::CollapsingHeader::new(title)
+        .id_salt("roster_preview")
+        .default_open(errors > 0)
+        .show(ui, |ui| {
+            if errors > 0 {
+                ui.colored_label(
+                    ERROR_RED,
+                    format!("{} line(s) need fixing before the draw", errors),
+                );
+            }
+            egui::ScrollArea::vertical()
+                .id_salt("roster_preview_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("roster_preview_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Line");
+                            ui.strong("Name");
+                            ui.strong("Tickets");
+                            ui.strong("Team");
+                            ui.strong("");
+                            ui.end_row();
+                            for row in &roster.rows {
+                                let entry = &row.entry;
+                                ui.label(row.line.to_string());
+                                ui.horizontal(|ui| {
+                                    if let Some([r, g, b]) = entry.color {
+                                        let (rect, _) = ui.allocate_exact_size(
+                                            egui::vec2(10.0, 10.0),
+                                            egui::Sense::hover(),
+                                        );
+                                        ui.painter().circle_filled(
+                                            rect.center(),
+                                            5.0,
+                                            egui::Color32::from_rgb(r, g, b),
+                                        );
+                                    }
+                                    let mut name = egui::RichText::new(&entry.name);
+                                    if entry.excluded {
+                                        name = name.strikethrough().weak();
+                                    }
+                                    ui.label(name);
+                                    let skin = entry.skin.to_text();
+                                    if !skin.is_empty() {
+                                        ui.label(egui::RichText::new(skin).weak());
+                                    }
+                                });
+                                ui.label(entry.tickets.to_string());
+                                ui.label(entry.team.as_deref().unwrap_or(""));
+                                if row.errors.is_empty() {
+                                    ui.label(if entry.excluded { "excluded" } else { "" });
+                                } else {
+                                    ui.colored_label(ERROR_RED, row.errors.join("; "));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+}
@@ -0,0 +1,112 @@
+use crate::game::skin::{self, Skin};
+use crate::game::Ball;
+use eframe::egui;
+use std::collections::HashMap;
+
+// Skin pictures are shrunk to this many pixels across
+const IMAGE_SIZE: u32 = 64;
+
+/// Pictures for `Skin::Image`, decoded once per file name. `None` marks a
+/// file that couldn't be loaded, so it isn't retried every frame.
+#[derive(Default)]
+pub struct SkinTextures {
+    textures: HashMap<String, Option<egui::TextureHandle>>,
+}
+
+impl SkinTextures {
+    /// Decode `bytes` and keep them under `name` (e.g. an image dropped onto
+    /// the window, which is how the web build gets pictures).
+    pub fn insert(&mut self, ctx: &egui::Context, name: &str, bytes: &[u8]) -> Result<(), String> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| format!("Could not read {}: {}", name, e))?;
+        let image = image::imageops::thumbnail(&image.to_rgba8(), IMAGE_SIZE, IMAGE_SIZE);
+        let pixels = egui::ColorImage::from_rgba_unmultiplied(
+            [image.width() as usize, image.height() as usize],
+            image.as_raw(),
+        );
+        let texture = ctx.load_texture(name, pixels, egui::TextureOptions::LINEAR);
+        self.textures.insert(name.to_owned(), Some(texture));
+        Ok(())
+    }
+
+    /// Texture for `name`, reading it from disk the first time on native.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    fn get(&mut self, ctx: &egui::Context, name: &str) -> Option<egui::TextureId> {
+        if !self.textures.contains_key(name) {
+            self.textures.insert(name.to_owned(), None);
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Ok(bytes) = std::fs::read(name) {
+                let _ = self.insert(ctx, name, &bytes);
+            }
+        }
+        self.textures[name].as_ref().map(|t| t.id())
+    }
+}
+
+/// Draw `ball`'s skin inside its circle.
+pub fn paint(
+    painter: &egui::Painter,
+    textures: &mut SkinTextures,
+    center: egui::Pos2,
+    radius: f32,
+    ball: &Ball,
+    owner: &str,
+) {
+    let [r, g, b] = ball.color;
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    let ink = if luminance > 150.0 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    };
+    match &ball.skin {
+        Skin::Plain => {}
+        Skin::Initials => {
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                skin::initials(owner),
+                egui::FontId::proportional(radius * 1.1),
+                ink,
+            );
+        }
+        Skin::Glyph(glyph) => {
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                glyph,
+                egui::FontId::proportional(radius * 1.4),
+                ink,
+            );
+        }
+        Skin::Image(name) => {
+            if let Some(texture) = textures.get(painter.ctx(), name) {
+                painter.add(disc_mesh(texture, center, radius - 1.0));
+            }
+        }
+    }
+}
+
+/// `texture` mapped onto a disc, so pictures stay round like the ball.
+fn disc_mesh(texture: egui::TextureId, center: egui::Pos2, radius: f32) -> egui::Mesh {
+    const SEGMENTS: u32 = 24;
+    let mut mesh = egui::Mesh::with_texture(texture);
+    mesh.vertices.push(egui::epaint::Vertex {
+        pos: center,
+        uv: egui::pos2(0.5, 0.5),
+        color: egui::Color32::WHITE,
+    });
+    for i in 0..=SEGMENTS {
+        let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let dir = egui::vec2(angle.cos(), angle.sin());
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: center + dir * radius,
+            uv: egui::pos2(0.5 + dir.x * 0.5, 0.5 + dir.y * 0.5),
+            color: egui::Color32::WHITE,
+        });
+        if i > 0 {
+            mesh.add_triangle(0, i, i + 1);
+        }
+    }
+    mesh
+}
@@ -0,0 +1,187 @@
+use crate::tournament::{Kind, MapRotation, Settings, Tournament};
+use eframe::egui;
+
+/// Settings for the next tournament, as they're being edited.
+pub struct TournamentView {
+    settings: Settings,
+    points: String, // `settings.points`, comma separated
+}
+
+impl Default for TournamentView {
+    fn default() -> Self {
+        let settings = Settings::default();
+        let points: Vec<String> = settings.points.iter().map(|p| p.to_string()).collect();
+        Self {
+            points: points.join(","),
+            settings,
+        }
+    }
+}
+
+pub enum TournamentAction {
+    Start(Settings),
+    End,
+}
+
+/// Sidebar section to set up a tournament from the roster, or follow the
+/// one under way: what's next, the overall standings and every round's
+/// results.
+pub fn show(
+    ui: &mut egui::Ui,
+    tournament: Option<&Tournament>,
+    view: &mut TournamentView,
+) -> Option<TournamentAction> {
+    let title = match tournament {
+        None => "Tournament".to_owned(),
+        Some(t) if t.is_finished() => format!("Tournament ({}, finished)", t.settings.kind.name()),
+        Some(t) => format!(
+            "Tournament ({}, round {})",
+            t.settings.kind.name(),
+            t.rounds.len()
+        ),
+    };
+    let mut action = None;
+    egui::CollapsingHeader::new(title)
+        .id_salt("tournament")
+        .show(ui, |ui| match tournament {
+            None => action = setup(ui, view),
+            Some(t) => {
+                progress(ui, t);
+                if ui.button("End Tournament").clicked() {
+                    action = Some(TournamentAction::End);
+                }
+            }
+        });
+    action
+}
+
+fn setup(ui: &mut egui::Ui, view: &mut TournamentView) -> Option<TournamentAction> {
+    let settings = &mut view.settings;
+    for kind in [Kind::Series, Kind::Heats, Kind::Bracket] {
+        ui.radio_value(&mut settings.kind, kind, kind.name());
+    }
+    match settings.kind {
+        Kind::Series => {
+            ui.horizontal(|ui| {
+                ui.label("Races:");
+                ui.add(egui::DragValue::new(&mut settings.races).range(1..=20));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Points per place:");
+                if ui.text_edit_singleline(&mut view.points).changed() {
+                    settings.points = view
+                        .points
+                        .split(',')
+                        .filter_map(|p| p.trim().parse().ok())
+                        .collect();
+                }
+            });
+        }
+        Kind::Heats | Kind::Bracket => {
+            ui.horizontal(|ui| {
+                ui.label("Heats of");
+                ui.add(egui::DragValue::new(&mut settings.heat_size).range(2..=32));
+                ui.label(", top");
+                let most = settings.heat_size - 1;
+                settings.advance = settings.advance.min(most);
+                ui.add(egui::DragValue::new(&mut settings.advance).range(1..=most));
+                ui.label("go through");
+            });
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.label("Map:");
+        ui.radio_value(&mut settings.maps, MapRotation::Same, "Same each round");
+        ui.radio_value(&mut settings.maps, MapRotation::Rotate, "New each round");
+    });
+    ui.label(
+        egui::RichText::new(
+            "Everyone on the roster takes part with their tickets for the next draw.",
+        )
+        .small(),
+    );
+    ui.button("Set Up Tournament")
+        .clicked()
+        .then(|| TournamentAction::Start(settings.clone()))
+}
+
+fn progress(ui: &mut egui::Ui, t: &Tournament) {
+    let series = t.settings.kind == Kind::Series;
+    match (t.next_race(), t.champion()) {
+        (Some((round, race)), _) => {
+            let names = &t.rounds[round].races[race].names;
+            ui.label(format!(
+                "Next: {} ({} people)",
+                t.race_label(round, race),
+                names.len()
+            ))
+            .on_hover_text(names.join(", "));
+            ui.label(format!("Map: {}", t.rounds[round].map.name));
+        }
+        (None, Some(champion)) => {
+            ui.label(
+                egui::RichText::new(format!("🏆 Champion: {}", champion))
+                    .strong()
+                    .color(egui::Color32::GOLD),
+            );
+        }
+        (None, None) => {}
+    }
+
+    ui.separator();
+    ui.label("Standings:");
+    egui::ScrollArea::vertical()
+        .id_salt("tournament_standings_scroll")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("tournament_standings")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("#");
+                    ui.strong("Name");
+                    ui.strong(if series { "Points" } else { "Reached" });
+                    ui.strong("Wins");
+                    ui.end_row();
+                    for (i, s) in t.standings().iter().enumerate() {
+                        ui.label(format!("{}.", i + 1));
+                        let mut name = egui::RichText::new(&s.name);
+                        if s.out {
+                            name = name.strikethrough().weak();
+                        }
+                        ui.label(name);
+                        if series {
+                            ui.label(s.points.to_string());
+                        } else {
+                            ui.label(&t.rounds[s.reached].name);
+                        }
+                        ui.label(s.wins.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+
+    for (r, round) in t.rounds.iter().enumerate() {
+        egui::CollapsingHeader::new(format!("{} ({})", round.name, round.map.name))
+            .id_salt(("tournament_round", r))
+            .show(ui, |ui| {
+                for (i, race) in round.races.iter().enumerate() {
+                    let label = t.race_label(r, i);
+                    let text = if race.result.is_empty() {
+                        format!("{}: {} (not run)", label, race.names.join(", "))
+                    } else {
+                        let placed: Vec<String> = race
+                            .result
+                            .iter()
+                            .enumerate()
+                            .map(|(place, name)| format!("{}. {}", place + 1, name))
+                            .collect();
+                        format!("{}: {}", label, placed.join("  "))
+                    };
+                    let response = ui.label(text);
+                    if let Some(seed) = race.seed {
+                        response.on_hover_text(format!("Seed {}", seed));
+                    }
+                }
+            });
+    }
+}
@@ -0,0 +1,241 @@
+use crate::game::map_file::MapFile;
+use crate::game::{CloneRank, WinningCondition};
+use crate::proof::{Check, Claim, Inputs, Verdict};
+use eframe::egui;
+
+/// What's been pasted into the verify window, and the last check's result.
+pub struct VerifyView {
+    pub open: bool,
+    seed: String,
+    roster: String,
+    use_board_map: bool,
+    map_json: String,
+    condition: WinningCondition,
+    clones: CloneRank,
+    commitment: String,
+    winner: String,
+    running: Option<Check>, // The check under way, run a chunk per frame
+    verdict: Option<Result<Verdict, String>>,
+}
+
+impl Default for VerifyView {
+    fn default() -> Self {
+        Self {
+            open: false,
+            seed: String::new(),
+            roster: String::new(),
+            use_board_map: true,
+            map_json: String::new(),
+            condition: WinningCondition::First,
+            clones: CloneRank::Best,
+            commitment: String::new(),
+            winner: String::new(),
+            running: None,
+            verdict: None,
+        }
+    }
+}
+
+impl VerifyView {
+    /// Open the window filled in with a draw run here.
+    pub fn fill(&mut self, inputs: &Inputs, winner: Option<&str>) {
+        self.open = true;
+        self.seed = inputs.seed.to_string();
+        self.roster = inputs.roster.clone();
+        self.use_board_map = true;
+        self.condition = inputs.condition;
+        self.clones = inputs.clones;
+        self.commitment = inputs.commitment();
+        self.winner = winner.unwrap_or_default().to_owned();
+        self.running = None;
+        self.verdict = None;
+    }
+}
+
+/// Window that re-runs a published draw from its seed, roster and map and
+/// checks the commitment and winner against it.
+pub fn show(ctx: &egui::Context, view: &mut VerifyView, board_map: &MapFile) {
+    let mut open = view.open;
+    egui::Window::new("Verify a Draw")
+        .open(&mut open)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            egui::Grid::new("verify_inputs")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Seed:");
+                    ui.text_edit_singleline(&mut view.seed);
+                    ui.end_row();
+                    ui.label("Condition:");
+                    egui::ComboBox::from_id_salt("verify_condition")
+                        .selected_text(view.condition.name())
+                        .show_ui(ui, |ui| {
+                            for condition in [
+                                WinningCondition::First,
+                                WinningCondition::Last,
+                                WinningCondition::LastStanding,
+                            ] {
+                                ui.selectable_value(
+                                    &mut view.condition,
+                                    condition,
+                                    condition.name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Split balls count:");
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut view.clones, CloneRank::Best, "Best");
+                        ui.selectable_value(&mut view.clones, CloneRank::Worst, "Worst");
+                    });
+                    ui.end_row();
+                    ui.label("Commitment:");
+                    ui.text_edit_singleline(&mut view.commitment)
+                        .on_hover_text("Published before the race (optional)");
+                    ui.end_row();
+                    ui.label("Winner:");
+                    ui.text_edit_singleline(&mut view.winner)
+                        .on_hover_text("Announced after the race (optional)");
+                    ui.end_row();
+                });
+
+            ui.label("Roster (as revealed, with the tickets each person raced with):");
+            ui.add(
+                egui::TextEdit::multiline(&mut view.roster)
+                    .desired_rows(4)
+                    .desired_width(f32::INFINITY),
+            );
+            ui.checkbox(
+                &mut view.use_board_map,
+                format!("Use the map on the board (\"{}\")", board_map.name),
+            );
+            if !view.use_board_map {
+                ui.label("Map file (.json):");
+                egui::ScrollArea::vertical()
+                    .id_salt("verify_map_scroll")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut view.map_json)
+                                .code_editor()
+                                .desired_rows(4)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+            }
+
+            if ui
+                .add_enabled(view.running.is_none(), egui::Button::new("Run and Check"))
+                .clicked()
+            {
+                view.verdict = None;
+                match start(view, board_map) {
+                    Ok(check) => view.running = Some(check),
+                    Err(e) => view.verdict = Some(Err(e)),
+                }
+            }
+
+            if let Some(check) = &mut view.running {
+                match check.step() {
+                    Some(verdict) => {
+                        view.verdict = Some(Ok(verdict));
+                        view.running = None;
+                    }
+                    None => {
+                        let (time, finished, total) = check.progress();
+                        ui.add(
+                            egui::ProgressBar::new(finished as f32 / total.max(1) as f32).text(
+                                format!(
+                                    "Running: {:.0} s raced, {} of {} finished",
+                                    time, finished, total
+                                ),
+                            ),
+                        );
+                        ui.ctx().request_repaint();
+                    }
+                }
+            }
+
+            match &view.verdict {
+                None => {}
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, e);
+                }
+                Some(Ok(verdict)) => show_verdict(ui, verdict),
+            }
+        });
+    if !open {
+        view.running = None; // Closing the window cancels a check
+    }
+    view.open = open;
+}
+
+fn start(view: &VerifyView, board_map: &MapFile) -> Result<Check, String> {
+    let seed = view
+        .seed
+        .trim()
+        .parse()
+        .map_err(|_| format!("Seed \"{}\" is not a number", view.seed.trim()))?;
+    let map = if view.use_board_map {
+        board_map.clone()
+    } else {
+        MapFile::from_json(&view.map_json)?
+    };
+    Check::start(Claim {
+        seed,
+        roster: &view.roster,
+        map,
+        condition: view.condition,
+        clones: view.clones,
+        commitment: &view.commitment,
+        winner: &view.winner,
+    })
+}
+
+fn show_verdict(ui: &mut egui::Ui, verdict: &Verdict) {
+    ui.separator();
+    let unchecked = ui.visuals().weak_text_color();
+    let mark = |ok: Option<bool>| match ok {
+        Some(true) => ("✔", egui::Color32::LIGHT_GREEN),
+        Some(false) => ("✖", egui::Color32::LIGHT_RED),
+        None => ("–", unchecked),
+    };
+    ui.horizontal(|ui| {
+        let (icon, color) = mark(verdict.commitment_ok);
+        ui.colored_label(color, icon);
+        ui.label("Commitment");
+        ui.monospace(&verdict.commitment[..16])
+            .on_hover_text(&verdict.commitment);
+    });
+    ui.horizontal(|ui| {
+        let (icon, color) = mark(verdict.winner_ok);
+        ui.colored_label(color, icon);
+        ui.label(format!(
+            "Winner: {}",
+            verdict.winner.as_deref().unwrap_or("none")
+        ));
+    });
+    if !verdict.finished {
+        ui.colored_label(
+            egui::Color32::LIGHT_RED,
+            "Gave up with balls still on the board",
+        );
+    }
+    if verdict.commitment_ok.is_none() && verdict.winner_ok.is_none() {
+        ui.label("Fill in the commitment or winner to check them.");
+    } else if verdict.passed() {
+        ui.colored_label(egui::Color32::LIGHT_GREEN, "This draw checks out.");
+    } else {
+        ui.colored_label(
+            egui::Color32::LIGHT_RED,
+            "This draw does not match what was published.",
+        );
+    }
+    egui::CollapsingHeader::new("Full ranking")
+        .id_salt("verify_ranking")
+        .show(ui, |ui| {
+            for (i, name) in verdict.ranking.iter().enumerate() {
+                ui.label(format!("{}. {}", i + 1, name));
+            }
+        });
+}